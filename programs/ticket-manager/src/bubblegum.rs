@@ -0,0 +1,219 @@
+// =============================================
+// Bubblegum CPI helpers
+// Hand-built instructions for the compressed ticket NFTs, so the program
+// doesn't need to depend on mpl-bubblegum
+// =============================================
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+pub const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Anchor discriminator for Bubblegum's `burn` (sha256("global:burn")[..8]).
+const BURN_DISCRIMINATOR: [u8; 8] = [116, 110, 29, 56, 107, 219, 42, 93];
+
+/// Anchor discriminator for Bubblegum's `mint_v1`.
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+
+/// Anchor discriminator for Bubblegum's `TreeConfig` account.
+const TREE_CONFIG_DISCRIMINATOR: [u8; 8] = [122, 245, 175, 248, 171, 34, 0, 207];
+
+/// Leaf data Bubblegum needs to prove a cNFT before modifying it.
+/// Clients fetch this (and the proof) from a DAS-compatible RPC.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CnftLeaf {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+/// Asset id Bubblegum assigns to the leaf minted at `nonce` in `merkle_tree`.
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &BUBBLEGUM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// `(num_minted, total_mint_capacity)` read from a Bubblegum `TreeConfig`,
+/// or `None` if the account isn't one. The next leaf minted gets nonce
+/// `num_minted`.
+pub fn tree_usage(tree_config: &AccountInfo) -> Option<(u64, u64)> {
+    if *tree_config.owner != BUBBLEGUM_PROGRAM_ID {
+        return None;
+    }
+    let data = tree_config.try_borrow_data().ok()?;
+    if data.get(..8)? != TREE_CONFIG_DISCRIMINATOR {
+        return None;
+    }
+    // Discriminator, tree_creator and tree_delegate come first.
+    let read_u64 = |at: usize| Some(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?));
+    let capacity = read_u64(8 + 32 + 32)?;
+    let num_minted = read_u64(8 + 32 + 32 + 8)?;
+    Some((num_minted, capacity))
+}
+
+/// Accounts for a Bubblegum `mint_v1` CPI. `tree_delegate` is signed for
+/// with the seeds passed to `mint`.
+pub struct MintAccounts<'a, 'info> {
+    pub bubblegum_program: &'a AccountInfo<'info>,
+    pub tree_config: &'a AccountInfo<'info>,
+    pub leaf_owner: &'a AccountInfo<'info>,
+    pub leaf_delegate: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub tree_delegate: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Mint a non-fungible compressed NFT with no creators or collection to
+/// `leaf_owner`, delegated to `leaf_delegate`.
+pub fn mint(
+    accounts: MintAccounts,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = Vec::with_capacity(8 + 12 + name.len() + symbol.len() + uri.len() + 12);
+    data.extend_from_slice(&MINT_V1_DISCRIMINATOR);
+    for text in [name, symbol, uri] {
+        data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        data.extend_from_slice(text.as_bytes());
+    }
+    data.extend_from_slice(&0u16.to_le_bytes()); // seller_fee_basis_points
+    data.push(1); // primary_sale_happened
+    data.push(0); // is_mutable
+    data.push(0); // edition_nonce: None
+    data.extend_from_slice(&[1, 0]); // token_standard: Some(NonFungible)
+    data.push(0); // collection: None
+    data.push(0); // uses: None
+    data.push(0); // token_program_version: Original
+    data.extend_from_slice(&0u32.to_le_bytes()); // creators: []
+
+    let metas = vec![
+        AccountMeta::new(accounts.tree_config.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.payer.key(), true),
+        AccountMeta::new_readonly(accounts.tree_delegate.key(), true),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+    ];
+    let infos = [
+        accounts.bubblegum_program.clone(),
+        accounts.tree_config.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_delegate.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.payer.clone(),
+        accounts.tree_delegate.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone(),
+    ];
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, signer_seeds)?;
+    Ok(())
+}
+
+/// Accounts for a Bubblegum `burn` CPI. `proof` is the Merkle proof path,
+/// in order.
+pub struct BurnAccounts<'a, 'info> {
+    pub bubblegum_program: &'a AccountInfo<'info>,
+    pub tree_config: &'a AccountInfo<'info>,
+    pub leaf_owner: &'a AccountInfo<'info>,
+    pub leaf_delegate: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub proof: &'a [AccountInfo<'info>],
+}
+
+/// Burn a compressed NFT via Bubblegum. `leaf_owner` must sign the outer
+/// instruction.
+pub fn burn(accounts: BurnAccounts, leaf: &CnftLeaf) -> Result<()> {
+    let (ix, infos) = burn_instruction(&accounts, leaf, true);
+    invoke(&ix, &infos)?;
+    Ok(())
+}
+
+/// Burn a compressed NFT as its leaf delegate, a PDA of this program signed
+/// for with `signer_seeds`.
+pub fn burn_as_delegate(
+    accounts: BurnAccounts,
+    leaf: &CnftLeaf,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let (ix, infos) = burn_instruction(&accounts, leaf, false);
+    invoke_signed(&ix, &infos, signer_seeds)?;
+    Ok(())
+}
+
+/// Build a `burn` instruction signed by the leaf owner, or by the leaf
+/// delegate when `owner_signs` is false.
+fn burn_instruction<'info>(
+    accounts: &BurnAccounts<'_, 'info>,
+    leaf: &CnftLeaf,
+    owner_signs: bool,
+) -> (Instruction, Vec<AccountInfo<'info>>) {
+    let mut data = Vec::with_capacity(8 + 32 * 3 + 8 + 4);
+    data.extend_from_slice(&BURN_DISCRIMINATOR);
+    data.extend_from_slice(&leaf.root);
+    data.extend_from_slice(&leaf.data_hash);
+    data.extend_from_slice(&leaf.creator_hash);
+    data.extend_from_slice(&leaf.nonce.to_le_bytes());
+    data.extend_from_slice(&leaf.index.to_le_bytes());
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.tree_config.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), owner_signs),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), !owner_signs),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+    ];
+    metas.extend(
+        accounts
+            .proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(node.key(), false)),
+    );
+
+    let mut infos = vec![
+        accounts.bubblegum_program.clone(),
+        accounts.tree_config.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_delegate.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone(),
+    ];
+    infos.extend(accounts.proof.iter().cloned());
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    (ix, infos)
+}
@@ -0,0 +1,81 @@
+// =============================================
+// Ed25519 signature introspection
+// Reads the Ed25519 precompile instruction that precedes ours in the
+// transaction, so off-chain signatures can be checked on-chain
+// =============================================
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::TicketError;
+
+/// Size of the header (count + padding) plus one `Ed25519SignatureOffsets`.
+const HEADER_LEN: usize = 2 + 14;
+
+/// Marks offsets that point into the Ed25519 instruction's own data.
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Return the `(signer, message)` pair verified by the Ed25519 precompile
+/// instruction immediately before the current one.
+///
+/// The precompile has already rejected the transaction if the signature is
+/// bad; this only checks that such an instruction exists and reads what it
+/// verified. Exactly one signature, with all data inline, is accepted.
+pub fn verified_message(instructions_sysvar: &AccountInfo) -> Result<(Pubkey, Vec<u8>)> {
+    let (signer, message, _) = verified_signature(instructions_sysvar)?;
+    Ok((signer, message))
+}
+
+/// Like `verified_message`, but also returns the verified signature, for
+/// callers that store it so it can be checked again off-chain.
+pub fn verified_signature(
+    instructions_sysvar: &AccountInfo,
+) -> Result<(Pubkey, Vec<u8>, [u8; 64])> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    require!(current > 0, TicketError::InvalidSignature);
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions_sysvar)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        TicketError::InvalidSignature
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= HEADER_LEN && data[0] == 1,
+        TicketError::InvalidSignature
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_offset = read_u16(2) as usize;
+    let signature_ix = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix = read_u16(14);
+    require!(
+        signature_ix == THIS_INSTRUCTION
+            && public_key_ix == THIS_INSTRUCTION
+            && message_ix == THIS_INSTRUCTION,
+        TicketError::InvalidSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(TicketError::InvalidSignature)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(TicketError::InvalidSignature)?;
+
+    let signature = data
+        .get(signature_offset..signature_offset + 64)
+        .ok_or(TicketError::InvalidSignature)?;
+
+    let signer = Pubkey::try_from(public_key).map_err(|_| TicketError::InvalidSignature)?;
+    let signature = signature.try_into().map_err(|_| TicketError::InvalidSignature)?;
+    Ok((signer, message.to_vec(), signature))
+}
@@ -0,0 +1,130 @@
+// =============================================
+// Fixed-size strings
+// Inline, length-prefixed UTF-8 for account fields, so short text costs
+// exactly its reserved bytes instead of a `String`'s prefix and slack
+// =============================================
+
+use anchor_lang::prelude::*;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::TicketError;
+
+/// Up to `N` bytes of UTF-8, stored as a length byte followed by `N` bytes
+/// (zero-padded). Derefs to `&str`, so it reads like a `String`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FixedStr<const N: usize> {
+    len: u8,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> FixedStr<N> {
+    /// Serialized size: length byte + `N` bytes.
+    pub const SIZE: usize = 1 + N;
+
+    pub fn as_str(&self) -> &str {
+        let bytes = self.bytes.get(..self.len as usize).unwrap_or_default();
+        std::str::from_utf8(bytes).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Default for FixedStr<N> {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            bytes: [0; N],
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for FixedStr<N> {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        require!(
+            value.len() <= N && N <= u8::MAX as usize,
+            TicketError::FieldTooLong
+        );
+        let mut bytes = [0; N];
+        bytes[..value.len()].copy_from_slice(value.as_bytes());
+        Ok(Self {
+            len: value.len() as u8,
+            bytes,
+        })
+    }
+}
+
+impl<const N: usize> TryFrom<String> for FixedStr<N> {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl<const N: usize> From<FixedStr<N>> for String {
+    fn from(value: FixedStr<N>) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl<const N: usize> Deref for FixedStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<str> for FixedStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<String> for FixedStr<N> {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ticket;
+
+    #[test]
+    fn rejects_a_200_char_event_name() {
+        let name = "n".repeat(200);
+        assert_eq!(
+            FixedStr::<{ Ticket::MAX_EVENT_NAME_LEN }>::try_from(name.as_str()).unwrap_err(),
+            TicketError::FieldTooLong.into()
+        );
+        assert_eq!(
+            Ticket::validate_fields("event", &name, "venue", "attendee", &None).unwrap_err(),
+            TicketError::FieldTooLong.into()
+        );
+    }
+
+    #[test]
+    fn round_trips_through_borsh() {
+        let text = FixedStr::<16>::try_from("A12").unwrap();
+        let bytes = text.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), FixedStr::<16>::SIZE);
+        let decoded = FixedStr::<16>::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded.as_str(), "A12");
+        assert_eq!(String::from(decoded), "A12");
+    }
+}
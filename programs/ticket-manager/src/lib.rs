@@ -4,173 +4,9373 @@
 // =============================================
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
 use anchor_lang::system_program;
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+pub mod bubblegum;
+pub mod ed25519;
+pub mod fixed;
+pub mod math;
+pub mod oracle;
+pub mod pyth;
+pub mod switchboard;
+
+use bubblegum::CnftLeaf;
+use fixed::FixedStr;
 
 declare_id!("9HUu9SZsUCbZL2Fd3dKBy2zzAKiMRVbP9y6QH5ZD1N5q"); // Replace after deploy
 
+/// Basis-point denominator (100%).
+pub const MAX_BPS: u16 = 10_000;
+
+/// Oldest Pyth price `purchase_ticket_usd` accepts, in seconds.
+pub const MAX_ORACLE_AGE: i64 = 60;
+/// Widest Pyth confidence interval accepted, relative to the price (2%).
+pub const MAX_ORACLE_CONF_BPS: u64 = 200;
+
+/// Most tickets a single `batch_purchase` may create, to stay within compute.
+/// Each ticket costs a `create_account` CPI, a PDA check and a Ticket
+/// serialization on top of the single payment, so compute grows linearly;
+/// together with the payout split recipients this stays well under
+/// `MAX_REMAINING_ACCOUNTS`. At the runtime's fixed prices (1.18 compute
+/// budget defaults) a ticket is about 4,400 CU: 1,000 to invoke plus 150
+/// for the System program, 1,500 per `find_program_address` attempt (two
+/// on average) and ~200 for the two seed hashes. Its Borsh serialization
+/// comes on top and hasn't been measured on-chain, so 10 is kept well
+/// below the 200,000 CU default.
+pub const MAX_BATCH_SIZE: usize = 10;
+/// Most tickets a single `batch_redeem` may scan, to stay within compute.
+pub const MAX_BATCH_REDEEM: usize = 20;
+/// Most entries a single `sweep_deposit_refunds` may close.
+pub const MAX_BATCH_REFUNDS: usize = 20;
+/// Most `remaining_accounts` any instruction reads. A transaction can lock
+/// at most 64 accounts, and one this large needs an address lookup table
+/// (a legacy message fits about 35); 40 leaves room for the instruction's
+/// named accounts and a compute-budget instruction. Exceeding it fails with
+/// `TooManyAccounts` before any work is done. Accounts are cheap to pass
+/// (CPIs are charged one CU per 250 bytes of account data); what they're
+/// used for costs compute, as priced on `MAX_BATCH_SIZE` and
+/// `Event::MAX_PAYOUT_SPLITS`.
+pub const MAX_REMAINING_ACCOUNTS: usize = 2 * MAX_BATCH_REFUNDS;
+
+/// Upper bound on the platform fee the admin can configure (10%).
+pub const MAX_PLATFORM_FEE_BPS: u16 = 1_000;
+
+/// Default redemption window: doors open one hour before `event_date`...
+pub const DEFAULT_REDEEM_OPENS_BEFORE: i64 = 60 * 60;
+/// ...and scanning stays open for a day after it.
+pub const DEFAULT_REDEEM_CLOSES_AFTER: i64 = 24 * 60 * 60;
+
+/// How long after `event_date` an unredeemed ticket may be closed (7 days).
+pub const CLOSE_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// Mint behind `Currency::Usdc` (mainnet USDC).
+pub const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+
 #[program]
 pub mod ticket_manager {
     use super::*;
 
-    /// Purchase and mint a ticket atomically.
-    /// Transfers SOL from buyer to venue, then creates a ticket PDA.
-    /// For free events, set price to 0 and payment is skipped.
-    pub fn purchase_ticket(
-        ctx: Context<PurchaseTicket>,
+    /// Create the program-wide Config singleton. The signer becomes the admin
+    /// who can pause purchases, redemptions and transfers in an incident,
+    /// and who sets the platform fee charged on SOL purchases.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        platform_fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        require!(
+            platform_fee_bps <= MAX_PLATFORM_FEE_BPS,
+            TicketError::InvalidFee
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.paused = false;
+        config.platform_fee_bps = platform_fee_bps;
+        config.treasury = treasury;
+        config.arbiter = Pubkey::default();
+        config.require_verified_venues = false;
+        config.feature_flags = Config::DEFAULT_FEATURES;
+        config.version = Config::CURRENT_VERSION;
+        config.bump = ctx.bumps.config;
+
+        msg!("Config initialized with admin {}", config.admin);
+        Ok(())
+    }
+
+    /// Pause or resume the program. Only callable by the config admin.
+    /// While paused, every instruction that checks Config fails with
+    /// `ProgramPaused`.
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.config.paused = paused;
+
+        msg!("Program {}", if paused { "paused" } else { "resumed" });
+        Ok(())
+    }
+
+    /// Change the platform fee and the treasury it is paid to.
+    /// Only callable by the config admin; capped at `MAX_PLATFORM_FEE_BPS`.
+    pub fn set_fee(
+        ctx: Context<UpdateConfig>,
+        platform_fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        require!(
+            platform_fee_bps <= MAX_PLATFORM_FEE_BPS,
+            TicketError::InvalidFee
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.platform_fee_bps = platform_fee_bps;
+        config.treasury = treasury;
+
+        msg!("Platform fee set to {} bps, paid to {}", platform_fee_bps, treasury);
+        Ok(())
+    }
+
+    /// Appoint the arbiter who resolves ticket disputes. Only callable by
+    /// the config admin; the default pubkey leaves disputes unresolvable.
+    pub fn set_arbiter(ctx: Context<UpdateConfig>, arbiter: Pubkey) -> Result<()> {
+        ctx.accounts.config.arbiter = arbiter;
+
+        msg!("Arbiter set to {}", arbiter);
+        Ok(())
+    }
+
+    /// Turn the feature bit `flag` (one of `Config::FEATURE_*`, or a bit
+    /// reserved for a feature not yet enabled) on or off. Only callable by
+    /// the config admin.
+    pub fn set_feature_flag(ctx: Context<UpdateConfig>, flag: u64, enabled: bool) -> Result<()> {
+        require!(flag.is_power_of_two(), TicketError::InvalidFeatureFlag);
+
+        let config = &mut ctx.accounts.config;
+        if enabled {
+            config.feature_flags |= flag;
+        } else {
+            config.feature_flags &= !flag;
+        }
+
+        msg!(
+            "Feature {:#x} {}",
+            flag,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
+
+    /// Record the program version this deployment runs. Only callable by
+    /// the config admin; versions never go backwards.
+    pub fn set_program_version(ctx: Context<UpdateConfig>, version: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(version >= config.version, TicketError::InvalidProgramVersion);
+        config.version = version;
+
+        msg!("Program version set to {}", version);
+        Ok(())
+    }
+
+    /// Register `authority` as a verified venue called `name`. Only
+    /// callable by the config admin.
+    pub fn register_venue(
+        ctx: Context<RegisterVenue>,
+        authority: Pubkey,
+        name: String,
+    ) -> Result<()> {
+        check_len("name", &name, Ticket::MAX_VENUE_LEN)?;
+
+        let venue = &mut ctx.accounts.verified_venue;
+        venue.authority = authority;
+        venue.name = name;
+        venue.verified_at = Clock::get()?.unix_timestamp;
+        venue.bump = ctx.bumps.verified_venue;
+
+        msg!("Venue {} verified as {}", authority, venue.name);
+        Ok(())
+    }
+
+    /// Remove a venue from the registry, returning the rent to the admin.
+    /// Events already created keep their `venue_verified` flag.
+    pub fn deregister_venue(_ctx: Context<DeregisterVenue>, authority: Pubkey) -> Result<()> {
+        msg!("Venue {} deregistered", authority);
+        Ok(())
+    }
+
+    /// Require every new event's venue authority to be a verified venue.
+    /// Only callable by the config admin.
+    pub fn set_require_verified_venues(ctx: Context<UpdateConfig>, required: bool) -> Result<()> {
+        ctx.accounts.config.require_verified_venues = required;
+
+        msg!(
+            "Verified venues {}",
+            if required { "required" } else { "optional" }
+        );
+        Ok(())
+    }
+
+    /// Create a shared Event PDA that tracks capacity for an event_id.
+    /// The signer becomes the event's organizer. The redemption window
+    /// defaults to one hour before `event_date` through one day after.
+    /// Tier capacities (GA, VIP, Backstage) must add up to `capacity`.
+    /// Also funds the event's escrow PDA to rent-exemption; SOL proceeds are
+    /// held there until `release_proceeds` pays `venue_authority`.
+    /// Passing the venue authority's `VerifiedVenue`, whose name must match
+    /// `venue`, marks the event and its tickets `venue_verified`; Config can
+    /// make that mandatory.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_event(
+        ctx: Context<CreateEvent>,
         event_id: String,
-        event_name: String,
+        venue: String,
+        venue_authority: Pubkey,
         event_date: i64,
+        capacity: u32,
+        max_per_buyer: u8,
+        redeem_window_start: Option<i64>,
+        redeem_window_end: Option<i64>,
+        tier_capacities: [u32; 3],
+        tier_prices: [u64; 3],
+    ) -> Result<()> {
+        check_len("event_id", &event_id, Ticket::MAX_EVENT_ID_LEN)?;
+        check_len("venue", &venue, Ticket::MAX_VENUE_LEN)?;
+        require!(capacity > 0, TicketError::InvalidCapacity);
+        let tier_total = tier_capacities
+            .iter()
+            .try_fold(0u32, |total, cap| total.checked_add(*cap))
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        require!(tier_total == capacity, TicketError::InvalidCapacity);
+        require!(max_per_buyer > 0, TicketError::InvalidPurchaseLimit);
+        let venue_verified = match &ctx.accounts.verified_venue {
+            Some(verified) => {
+                require!(verified.name == venue, TicketError::UnverifiedVenue);
+                true
+            }
+            None => false,
+        };
+        require!(
+            venue_verified || !ctx.accounts.config.require_verified_venues,
+            TicketError::UnverifiedVenue
+        );
+
+        let redeem_window_start = redeem_window_start
+            .unwrap_or_else(|| event_date.saturating_sub(DEFAULT_REDEEM_OPENS_BEFORE));
+        let redeem_window_end = redeem_window_end
+            .unwrap_or_else(|| event_date.saturating_add(DEFAULT_REDEEM_CLOSES_AFTER));
+        require!(
+            redeem_window_start <= redeem_window_end,
+            TicketError::InvalidRedemptionWindow
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.event_id = event_id;
+        event.organizer = ctx.accounts.organizer.key();
+        event.venue = venue;
+        event.event_date = event_date;
+        event.capacity = capacity;
+        event.tickets_sold = 0;
+        event.max_per_buyer = max_per_buyer;
+        event.redeem_window_start = redeem_window_start;
+        event.redeem_window_end = redeem_window_end;
+        event.tier_capacities = tier_capacities;
+        event.tier_prices = tier_prices;
+        event.tier_sold = [0; 3];
+        // Paid resales stay disabled until the organizer calls set_resale_policy
+        event.max_resale_price = 0;
+        event.royalty_bps = 0;
+        event.venue_authority = venue_authority;
+        event.escrow_bump = ctx.bumps.escrow;
+        event.status = EventStatus::Scheduled;
+        event.rescheduled_from = 0;
+        event.allowlist_root = None;
+        event.presale_open = false;
+        event.sales_start = 0;
+        event.sales_end = 0;
+        event.reentry_allowed = false;
+        event.redemption_delegates = Vec::new();
+        event.price_usd_cents = 0;
+        event.refund_policy = Vec::new();
+        event.max_transfers = u8::MAX;
+        event.lottery = None;
+        event.refund_deadline = 0;
+        event.payout_splits = Vec::new();
+        event.early_bird_deadline = 0;
+        event.early_bird_bps = 0;
+        event.withdrawable = 0;
+        event.locked = 0;
+        event.redeemed_count = 0;
+        event.no_show_count = 0;
+        event.late_entry_grace = 0;
+        event.insurance_premium = 0;
+        event.insurance_pool = 0;
+        event.insurance_bump = 0;
+        event.co_organizers = Vec::new();
+        event.poap_tree = Pubkey::default();
+        event.venue_stops = 0;
+        event.waitlist_next = 0;
+        event.waitlist_head = 0;
+        event.pricing_mode = PricingMode::Fixed;
+        event.venue_verified = venue_verified;
+        event.rating_sum = 0;
+        event.rating_count = 0;
+        event.referral_reward_bps = 0;
+        event.referral_budget = 0;
+        event.referral_paid = 0;
+        event.tz_offset_minutes = 0;
+        event.currency = Currency::Sol;
+        event.attester = Pubkey::default();
+        event.required_attestation = 0;
+        event.terms_hash = [0u8; 32];
+        event.days = 1;
+        event.revenue_cap = 0;
+        event.gross_revenue = 0;
+        event.group_discounts = Vec::new();
+        event.original_buyer_royalty_bps = 0;
+        event.min_tickets = 0;
+        event.confirm_by = 0;
+        event.confirmed = false;
+        event.event_name = String::new();
+        event.metadata_uri = String::new();
+        event.enforce_royalty_on_transfer = false;
+        event.min_resale_price = 0;
+        event.free_transfer_recipients = Vec::new();
+        event.bump = ctx.bumps.event;
+        return_rent_surplus(&event.to_account_info(), &ctx.accounts.organizer.to_account_info())?;
+        update_profile(&mut ctx.accounts.profile, OrganizerProfile::record_event_created)?;
+
+        // Seed the escrow with its rent-exempt minimum so any later deposit,
+        // however small, leaves it a valid system account.
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let escrow_balance = ctx.accounts.escrow.lamports();
+        if escrow_balance < rent_exempt {
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.organizer.to_account_info(),
+                &ctx.accounts.escrow.to_account_info(),
+                rent_exempt - escrow_balance,
+            )?;
+        }
+
+        msg!("Event {} created with capacity {}", event.event_id, event.capacity);
+        Ok(())
+    }
+
+    /// Create `event_id` as a copy of the organizer's existing `source` event,
+    /// e.g. next week's show in a residency. Capacity, tiers, pricing,
+    /// splits, refund and resale rules and delegates are copied; other dates
+    /// (sales, redemption window, early bird, refund deadline) move with
+    /// `event_date`. Sales, escrow, lottery, allowlist, insurance and venue
+    /// stops start fresh. Fails if `event_id` is already in use.
+    pub fn clone_event(ctx: Context<CloneEvent>, event_id: String, event_date: i64) -> Result<()> {
+        check_len("event_id", &event_id, Ticket::MAX_EVENT_ID_LEN)?;
+
+        let template = ctx.accounts.source.template_for(
+            event_id,
+            event_date,
+            ctx.bumps.escrow,
+            ctx.bumps.event,
+        );
+        let event = &mut ctx.accounts.event;
+        event.set_inner(template);
+        return_rent_surplus(&event.to_account_info(), &ctx.accounts.organizer.to_account_info())?;
+        update_profile(&mut ctx.accounts.profile, OrganizerProfile::record_event_created)?;
+
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let escrow_balance = ctx.accounts.escrow.lamports();
+        if escrow_balance < rent_exempt {
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.organizer.to_account_info(),
+                &ctx.accounts.escrow.to_account_info(),
+                rent_exempt - escrow_balance,
+            )?;
+        }
+
+        msg!(
+            "Event {} cloned from {} for {}",
+            event.event_id,
+            ctx.accounts.source.event_id,
+            event_date
+        );
+        Ok(())
+    }
+
+    /// Configure secondary-market rules for an event.
+    /// Only callable by the organizer. Resales are capped at `max_resale_price`
+    /// lamports and pay `royalty_bps` of the sale price to the organizer.
+    pub fn set_resale_policy(
+        ctx: Context<UpdateEvent>,
+        max_resale_price: u64,
+        royalty_bps: u16,
+        original_buyer_royalty_bps: u16,
+    ) -> Result<()> {
+        require!(
+            royalty_bps as u32 + original_buyer_royalty_bps as u32 <= MAX_BPS as u32,
+            TicketError::InvalidRoyalty
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.max_resale_price = max_resale_price;
+        event.royalty_bps = royalty_bps;
+        event.original_buyer_royalty_bps = original_buyer_royalty_bps;
+
+        msg!(
+            "Resale policy for {}: max {} lamports, {} bps royalty, {} bps to original buyers",
+            event.event_id,
+            max_resale_price,
+            royalty_bps,
+            original_buyer_royalty_bps
+        );
+        Ok(())
+    }
+
+    /// Close the free-transfer loophole around resale royalties. While
+    /// `enforce` is set, `transfer_ticket` only moves tickets to the
+    /// organizer or one of `free_transfer_recipients` (at most
+    /// `Event::MAX_FREE_TRANSFER_RECIPIENTS`); every other change of owner
+    /// must be a sale of at least `min_resale_price` lamports, which pays
+    /// the royalty. Only callable by the organizer.
+    pub fn set_transfer_enforcement(
+        ctx: Context<UpdateEvent>,
+        enforce: bool,
+        min_resale_price: u64,
+        free_transfer_recipients: Vec<Pubkey>,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(
+            free_transfer_recipients.len() <= Event::MAX_FREE_TRANSFER_RECIPIENTS
+                && min_resale_price <= event.max_resale_price
+                && (!enforce || min_resale_price > 0),
+            TicketError::InvalidTransferPolicy
+        );
+
+        event.enforce_royalty_on_transfer = enforce;
+        event.min_resale_price = min_resale_price;
+        event.free_transfer_recipients = free_transfer_recipients;
+
+        msg!(
+            "Royalty enforcement for {} {}, resale minimum {} lamports",
+            event.event_id,
+            if enforce { "on" } else { "off" },
+            min_resale_price
+        );
+        Ok(())
+    }
+
+    /// Set when tickets go on sale. Only callable by the organizer.
+    /// Purchases are rejected before `sales_start` and after `sales_end`;
+    /// a `sales_end` of 0 keeps sales open until the event sells out.
+    pub fn set_sales_window(
+        ctx: Context<UpdateEvent>,
+        sales_start: i64,
+        sales_end: i64,
+    ) -> Result<()> {
+        require!(
+            sales_end == 0 || sales_end > sales_start,
+            TicketError::InvalidSalesWindow
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.sales_start = sales_start;
+        event.sales_end = sales_end;
+
+        msg!(
+            "Sales window for {}: {} to {}",
+            event.event_id,
+            sales_start,
+            sales_end
+        );
+        Ok(())
+    }
+
+    /// Price the event in US dollars for `purchase_ticket_usd`.
+    /// Only callable by the organizer; 0 disables USD pricing.
+    pub fn set_usd_price(ctx: Context<UpdateEvent>, price_usd_cents: u64) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.price_usd_cents = price_usd_cents;
+
+        msg!("USD price for {} set to {} cents", event.event_id, price_usd_cents);
+        Ok(())
+    }
+
+    /// Set time-tiered cancellation fees. Only callable by the organizer.
+    /// Each tier refunds `refund_bps` when cancelled more than
+    /// `seconds_before` the event; tiers must be sorted by `seconds_before`,
+    /// descending. Later than every tier, nothing is refunded. An empty
+    /// policy refunds in full.
+    pub fn set_refund_policy(
+        ctx: Context<UpdateEvent>,
+        refund_policy: Vec<RefundTier>,
+    ) -> Result<()> {
+        require!(
+            refund_policy.len() <= Event::MAX_REFUND_TIERS,
+            TicketError::InvalidRefundPolicy
+        );
+        require!(
+            refund_policy.iter().all(|tier| tier.refund_bps <= MAX_BPS)
+                && refund_policy
+                    .windows(2)
+                    .all(|pair| pair[0].seconds_before > pair[1].seconds_before),
+            TicketError::InvalidRefundPolicy
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.refund_policy = refund_policy;
+
+        msg!(
+            "Refund policy for {} set with {} tiers",
+            event.event_id,
+            event.refund_policy.len()
+        );
+        Ok(())
+    }
+
+    /// Set quantity discounts for `batch_purchase`. Only callable by the
+    /// organizer. A batch of at least `min_qty` tickets gets `discount_bps`
+    /// off each, using the largest tier it qualifies for; tiers must be
+    /// sorted by `min_qty`, ascending. An empty list turns them off.
+    pub fn set_group_discounts(
+        ctx: Context<UpdateEvent>,
+        group_discounts: Vec<GroupDiscount>,
+    ) -> Result<()> {
+        require!(
+            group_discounts.len() <= Event::MAX_GROUP_DISCOUNTS,
+            TicketError::InvalidGroupDiscount
+        );
+        require!(
+            group_discounts
+                .iter()
+                .all(|tier| tier.min_qty > 1 && tier.discount_bps <= MAX_BPS)
+                && group_discounts
+                    .windows(2)
+                    .all(|pair| pair[0].min_qty < pair[1].min_qty),
+            TicketError::InvalidGroupDiscount
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.group_discounts = group_discounts;
+
+        msg!(
+            "Group discounts for {} set with {} tiers",
+            event.event_id,
+            event.group_discounts.len()
+        );
+        Ok(())
+    }
+
+    /// Limit how many times each ticket may change hands, by transfer, resale
+    /// or listing sale. Only callable by the organizer. 0 makes tickets
+    /// non-transferable; `u8::MAX` (the default) means unlimited.
+    pub fn set_max_transfers(ctx: Context<UpdateEvent>, max_transfers: u8) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.max_transfers = max_transfers;
+
+        msg!("Max transfers for {} set to {}", event.event_id, max_transfers);
+        Ok(())
+    }
+
+    /// Keep accepting scans for `late_entry_grace` seconds after the event
+    /// starts, even if the redemption window closes sooner. Only callable by
+    /// the organizer; 0 (the default) means no grace.
+    pub fn set_late_entry_grace(ctx: Context<UpdateEvent>, late_entry_grace: i64) -> Result<()> {
+        require!(late_entry_grace >= 0, TicketError::InvalidGracePeriod);
+        let event = &mut ctx.accounts.event;
+        event.late_entry_grace = late_entry_grace;
+
+        msg!("Late entry grace for {} set to {}s", event.event_id, late_entry_grace);
+        Ok(())
+    }
+
+    /// Offer refund protection for `insurance_premium` lamports per ticket.
+    /// Only callable by the organizer; 0 stops offering it. The first call
+    /// funds the insurance pool PDA to rent-exemption so premiums can land.
+    pub fn set_insurance_premium(
+        ctx: Context<SetInsurancePremium>,
+        insurance_premium: u64,
+    ) -> Result<()> {
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let pool = ctx.accounts.insurance_pool.to_account_info();
+        if pool.lamports() < rent_exempt {
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.organizer.to_account_info(),
+                &pool,
+                rent_exempt - pool.lamports(),
+            )?;
+        }
+
+        let event = &mut ctx.accounts.event;
+        event.insurance_premium = insurance_premium;
+        event.insurance_bump = ctx.bumps.insurance_pool;
+
+        msg!("Insurance premium for {} set to {}", event.event_id, insurance_premium);
+        Ok(())
+    }
+
+    /// Set the tree `mint_poap` mints compressed proof-of-attendance NFTs
+    /// from for redeemed tickets. Only callable by the organizer; the
+    /// default pubkey turns POAPs off. The tree's delegate must be this
+    /// event's `[b"poap", event]` PDA, which signs the mints.
+    pub fn set_poap_tree(ctx: Context<UpdateEvent>, poap_tree: Pubkey) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.poap_tree = poap_tree;
+
+        msg!("POAP tree for {} set to {}", event.event_id, poap_tree);
+        Ok(())
+    }
+
+    /// Let holders `request_refund` until `refund_deadline`. Only callable by
+    /// the organizer; 0 (the default) disables voluntary refunds.
+    pub fn set_refund_deadline(ctx: Context<UpdateEvent>, refund_deadline: i64) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.refund_deadline = refund_deadline;
+
+        msg!("Refund deadline for {} set to {}", event.event_id, refund_deadline);
+        Ok(())
+    }
+
+    /// Split SOL proceeds between co-promoters. Only callable by the
+    /// organizer. Shares must sum to exactly 10000 bps across at most
+    /// `Event::MAX_PAYOUT_SPLITS` recipients; an empty list sends proceeds to
+    /// escrow as usual. Split proceeds are paid at purchase time, so they
+    /// don't sit in escrow for refunds.
+    pub fn set_payout_splits(
+        ctx: Context<UpdateEvent>,
+        payout_splits: Vec<PayoutSplit>,
+    ) -> Result<()> {
+        validate_payout_splits(&payout_splits)?;
+
+        let event = &mut ctx.accounts.event;
+        event.payout_splits = payout_splits;
+
+        msg!(
+            "Payout for {} split {} ways",
+            event.event_id,
+            event.payout_splits.len()
+        );
+        Ok(())
+    }
+
+    /// Register a discount code for an event. Only callable by the organizer.
+    /// Only the SHA-256 `code_hash` is stored; buyers present the code itself
+    /// to `purchase_ticket_with_promo`, at most `max_uses` times in total.
+    pub fn create_promo_code(
+        ctx: Context<CreatePromoCode>,
+        event_id: String,
+        code_hash: [u8; 32],
+        discount_bps: u16,
+        max_uses: u32,
+    ) -> Result<()> {
+        require!(discount_bps <= MAX_BPS, TicketError::InvalidDiscount);
+
+        let promo = &mut ctx.accounts.promo;
+        promo.event = ctx.accounts.event.key();
+        promo.code_hash = code_hash;
+        promo.discount_bps = discount_bps;
+        promo.max_uses = max_uses;
+        promo.uses = 0;
+        promo.bump = ctx.bumps.promo;
+
+        msg!(
+            "Promo code for {}: {} bps off, {} uses",
+            event_id,
+            discount_bps,
+            max_uses
+        );
+        Ok(())
+    }
+
+    /// Add a tour stop at `venue` with its own `capacity`. Only callable by
+    /// the organizer. Once an event has any stops, every purchase must pass
+    /// the `VenueStop` for its `venue` and counts against that stop as well
+    /// as the event's overall and tier capacity.
+    pub fn create_venue_stop(
+        ctx: Context<CreateVenueStop>,
+        event_id: String,
         venue: String,
-        attendee_name: String,
-        price_lamports: u64,
-        cnft_asset_id: Pubkey,
+        capacity: u32,
     ) -> Result<()> {
-        // Transfer SOL if not a free event
-        if price_lamports > 0 {
-            let cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.venue.to_account_info(),
-                },
-            );
-            system_program::transfer(cpi_context, price_lamports)?;
-            msg!("Payment of {} lamports transferred to venue", price_lamports);
-        } else {
-            msg!("Free event — no payment required");
+        check_len("venue", &venue, Ticket::MAX_VENUE_LEN)?;
+
+        let stop = &mut ctx.accounts.stop;
+        stop.event = ctx.accounts.event.key();
+        stop.venue = venue;
+        stop.capacity = capacity;
+        stop.tickets_sold = 0;
+        stop.bump = ctx.bumps.stop;
+
+        let event = &mut ctx.accounts.event;
+        event.venue_stops = event
+            .venue_stops
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+
+        msg!("Stop {} added to {} with capacity {}", stop.venue, event_id, capacity);
+        Ok(())
+    }
+
+    /// Ban `wallet` from buying, claiming or receiving tickets to this event.
+    /// Only callable by the organizer.
+    pub fn blacklist_attendee(
+        ctx: Context<BlacklistAttendee>,
+        event_id: String,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        let entry = &mut ctx.accounts.entry;
+        entry.event = ctx.accounts.event.key();
+        entry.wallet = wallet;
+        entry.bump = ctx.bumps.entry;
+
+        msg!("{} blacklisted from {}", wallet, event_id);
+        Ok(())
+    }
+
+    /// Lift a ban, closing the `BlacklistEntry` and returning its rent to the
+    /// organizer. Only callable by the organizer.
+    pub fn unblacklist_attendee(
+        _ctx: Context<UnblacklistAttendee>,
+        event_id: String,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        msg!("{} removed from the {} blacklist", wallet, event_id);
+        Ok(())
+    }
+
+    /// Discount every tier by `early_bird_bps` for purchases made before
+    /// `early_bird_deadline`. Only callable by the organizer; a deadline of 0
+    /// turns the discount off.
+    pub fn set_early_bird(
+        ctx: Context<UpdateEvent>,
+        early_bird_deadline: i64,
+        early_bird_bps: u16,
+    ) -> Result<()> {
+        require!(early_bird_bps <= MAX_BPS, TicketError::InvalidDiscount);
+
+        let event = &mut ctx.accounts.event;
+        event.early_bird_deadline = early_bird_deadline;
+        event.early_bird_bps = early_bird_bps;
+
+        msg!(
+            "Early bird for {}: {} bps off until {}",
+            event.event_id,
+            early_bird_bps,
+            early_bird_deadline
+        );
+        Ok(())
+    }
+
+    /// Choose how tickets are priced. `LinearCurve { base, step }` charges
+    /// `base + step * tickets_sold` for the next ticket, whatever its tier;
+    /// `DutchAuction` starts at `start_price` and falls by `decay_per_sec`
+    /// from `start_time` down to `floor_price`. Only callable by the
+    /// organizer.
+    pub fn set_pricing_mode(ctx: Context<UpdateEvent>, pricing_mode: PricingMode) -> Result<()> {
+        if let PricingMode::DutchAuction {
+            start_price,
+            floor_price,
+            ..
+        } = pricing_mode
+        {
+            require!(floor_price <= start_price, TicketError::InvalidAuction);
         }
 
-        // Initialize the ticket PDA
-        let ticket = &mut ctx.accounts.ticket;
-        ticket.event_id = event_id;
-        ticket.event_name = event_name;
-        ticket.event_date = event_date;
-        ticket.venue = venue;
-        ticket.attendee_name = attendee_name;
-        ticket.price_paid = price_lamports;
-        ticket.ticket_status = TicketStatus::Active;
-        ticket.cnft_asset_id = cnft_asset_id;
-        ticket.owner = ctx.accounts.buyer.key();
-        ticket.created_at = Clock::get()?.unix_timestamp;
-        ticket.bump = ctx.bumps.ticket;
+        let event = &mut ctx.accounts.event;
+        event.pricing_mode = pricing_mode;
+
+        msg!("Pricing for {} set to {:?}", event.event_id, pricing_mode);
+        Ok(())
+    }
+
+    /// Choose what the event's tickets are paid in. SOL events sell through
+    /// `purchase_ticket` and its variants, token events through
+    /// `purchase_ticket_spl` with the currency's mint; the other path fails
+    /// with `CurrencyMismatch`. Only callable by the organizer, before the
+    /// first ticket is sold.
+    pub fn set_currency(ctx: Context<UpdateEvent>, currency: Currency) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(event.tickets_sold == 0, TicketError::CurrencyLocked);
+        event.currency = currency;
+
+        msg!("Currency for {} set to {:?}", event.event_id, currency);
+        Ok(())
+    }
+
+    /// Publish the hash of the event's current terms and conditions. Buyers
+    /// must pass the same hash to purchase, and it's recorded on their
+    /// ticket as `accepted_terms`, pinning the version each one agreed to.
+    /// Only callable by the organizer; a zero hash means no terms.
+    pub fn set_terms_hash(ctx: Context<UpdateEvent>, terms_hash: [u8; 32]) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.terms_hash = terms_hash;
+
+        msg!("Terms for {} updated", event.event_id);
+        Ok(())
+    }
+
+    /// Gate purchases on an `Attestation` from `attester` that the buyer
+    /// meets `requirement` (e.g. `Attestation::AGE_21_PLUS`). Only callable
+    /// by the organizer; a requirement of 0 removes the gate.
+    pub fn set_attestation_requirement(
+        ctx: Context<UpdateEvent>,
+        attester: Pubkey,
+        requirement: u8,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.attester = attester;
+        event.required_attestation = requirement;
+
+        msg!(
+            "Attestation {} from {} required for {}",
+            requirement,
+            attester,
+            event.event_id
+        );
+        Ok(())
+    }
+
+    /// Vouch that `wallet` meets `requirement` until `expires_at`, or, with
+    /// `verified` false, that it doesn't. Only the attester signs; the
+    /// account records nothing but the answer and its expiry. Re-issuing
+    /// overwrites the previous answer.
+    pub fn issue_attestation(
+        ctx: Context<IssueAttestation>,
+        wallet: Pubkey,
+        requirement: u8,
+        verified: bool,
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(requirement != 0, TicketError::InvalidAttestation);
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            TicketError::InvalidAttestation
+        );
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.attester = ctx.accounts.attester.key();
+        attestation.wallet = wallet;
+        attestation.requirement = requirement;
+        attestation.verified = verified;
+        attestation.expires_at = expires_at;
+        attestation.bump = ctx.bumps.attestation;
+
+        msg!(
+            "Attestation {} for {}: {} until {}",
+            requirement,
+            wallet,
+            verified,
+            expires_at
+        );
+        Ok(())
+    }
+
+    /// Withdraw an attestation, returning its rent to the attester.
+    pub fn revoke_attestation(
+        _ctx: Context<RevokeAttestation>,
+        wallet: Pubkey,
+        requirement: u8,
+    ) -> Result<()> {
+        msg!("Attestation {} for {} revoked", requirement, wallet);
+        Ok(())
+    }
+
+    /// Run the event over `days` consecutive days (1 to
+    /// `Event::MAX_DAYS`), so each ticket admits once per day instead of
+    /// once. Day `i` starts `i` days after `event_date`; the redemption
+    /// window should cover all of them. Only callable by the organizer.
+    pub fn set_festival_days(ctx: Context<UpdateEvent>, days: u8) -> Result<()> {
+        require!(
+            (1..=Event::MAX_DAYS).contains(&days),
+            TicketError::InvalidFestivalDays
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.days = days;
+
+        msg!("{} runs for {} days", event.event_id, days);
+        Ok(())
+    }
+
+    /// Stop selling once `revenue_cap` has been collected, whatever capacity
+    /// is left, e.g. for a charity target. `gross_revenue` counts what buyers
+    /// actually paid, after discounts and before refunds; a sale that would
+    /// take it past the cap fails with `RevenueCapReached`. Only callable by
+    /// the organizer; 0 removes the cap.
+    pub fn set_revenue_cap(ctx: Context<UpdateEvent>, revenue_cap: u64) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.revenue_cap = revenue_cap;
+
+        msg!(
+            "Revenue cap for {} set to {} ({} collected)",
+            event.event_id,
+            revenue_cap,
+            event.gross_revenue
+        );
+        Ok(())
+    }
+
+    /// Make the event conditional on selling `min_tickets` by `confirm_by`.
+    /// The event confirms as soon as the threshold is reached; if it hasn't
+    /// been by the deadline, anyone can `auto_cancel_undersubscribed`. Only
+    /// callable by the organizer, before confirmation; 0 removes the
+    /// threshold.
+    pub fn set_min_sales(
+        ctx: Context<UpdateEvent>,
+        min_tickets: u32,
+        confirm_by: i64,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(!event.confirmed, TicketError::MinSalesMet);
+        require!(
+            min_tickets <= event.capacity && (min_tickets == 0 || confirm_by <= event.event_date),
+            TicketError::InvalidMinSales
+        );
+
+        event.min_tickets = min_tickets;
+        event.confirm_by = if min_tickets == 0 { 0 } else { confirm_by };
+        event.confirmed = min_tickets > 0 && event.tickets_sold >= min_tickets;
+
+        msg!(
+            "Event {} needs {} tickets sold by {} ({} sold)",
+            event.event_id,
+            min_tickets,
+            event.confirm_by,
+            event.tickets_sold
+        );
+        Ok(())
+    }
+
+    /// Fix the event's display name, venue or metadata URI. Only callable by
+    /// the organizer. Once tickets are sold these are locked against
+    /// bait-and-switch unless `force` is set with a non-empty `reason`, which
+    /// is logged. Renaming the venue clears `venue_verified`, since the
+    /// registry vouched for the old name; issued tickets keep their copies.
+    pub fn update_event_metadata(
+        ctx: Context<UpdateEvent>,
+        event_name: String,
+        venue: String,
+        metadata_uri: String,
+        force: bool,
+        reason: String,
+    ) -> Result<()> {
+        check_len("event_name", &event_name, Ticket::MAX_EVENT_NAME_LEN)?;
+        check_len("venue", &venue, Ticket::MAX_VENUE_LEN)?;
+        check_len("metadata_uri", &metadata_uri, Ticket::MAX_METADATA_URI_LEN)?;
+        check_len("reason", &reason, Event::MAX_UPDATE_REASON_LEN)?;
+
+        let event = &mut ctx.accounts.event;
+        let forced = event.tickets_sold > 0;
+        require!(
+            !forced || (force && !reason.is_empty()),
+            TicketError::MetadataLocked
+        );
+        if forced {
+            msg!("Forced metadata update for {}: {}", event.event_id, reason);
+        }
+
+        if venue != event.venue {
+            event.venue_verified = false;
+        }
+        let old_event_name = std::mem::replace(&mut event.event_name, event_name);
+        let old_venue = std::mem::replace(&mut event.venue, venue);
+        let old_metadata_uri = std::mem::replace(&mut event.metadata_uri, metadata_uri);
+
+        msg!("Metadata for {} updated", event.event_id);
+        emit!(EventMetadataUpdated {
+            event: event.key(),
+            old_event_name,
+            new_event_name: event.event_name.clone(),
+            old_venue,
+            new_venue: event.venue.clone(),
+            old_metadata_uri,
+            new_metadata_uri: event.metadata_uri.clone(),
+            forced,
+            reason,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Record the venue's local offset from UTC, in minutes, so wallets can
+    /// show `event_date` and door times in local time. Only callable by the
+    /// organizer. Timestamps and redemption windows stay in UTC.
+    pub fn set_tz_offset(ctx: Context<UpdateEvent>, tz_offset_minutes: i16) -> Result<()> {
+        require!(
+            tz_offset_minutes.abs() <= Event::MAX_TZ_OFFSET_MINUTES,
+            TicketError::InvalidTzOffset
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.tz_offset_minutes = tz_offset_minutes;
+
+        msg!("UTC offset for {} set to {} minutes", event.event_id, tz_offset_minutes);
+        Ok(())
+    }
+
+    /// Pay referrers `referral_reward_bps` of each referred sale, taken out
+    /// of the platform fee, until `referral_budget` lamports have been paid.
+    /// Only callable by the organizer; a rate of 0 turns referrals off.
+    pub fn set_referral_program(
+        ctx: Context<UpdateEvent>,
+        referral_reward_bps: u16,
+        referral_budget: u64,
+    ) -> Result<()> {
+        require!(referral_reward_bps <= MAX_BPS, TicketError::InvalidFee);
+
+        let event = &mut ctx.accounts.event;
+        event.referral_reward_bps = referral_reward_bps;
+        event.referral_budget = referral_budget;
+
+        msg!(
+            "Referrals for {}: {} bps, budget {} lamports ({} paid)",
+            event.event_id,
+            referral_reward_bps,
+            referral_budget,
+            event.referral_paid
+        );
+        Ok(())
+    }
+
+    /// Allow or forbid re-entry (repeat scans of a Redeemed ticket).
+    /// Only callable by the organizer.
+    pub fn set_reentry(ctx: Context<UpdateEvent>, reentry_allowed: bool) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.reentry_allowed = reentry_allowed;
+
+        msg!(
+            "Re-entry for {} is {}",
+            event.event_id,
+            if reentry_allowed { "allowed" } else { "not allowed" }
+        );
+        Ok(())
+    }
+
+    /// Authorize a gate scanner key to redeem tickets for this event.
+    /// Only callable by the organizer; at most `Event::MAX_DELEGATES` keys.
+    pub fn add_delegate(ctx: Context<UpdateEvent>, delegate: Pubkey) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(
+            !event.redemption_delegates.contains(&delegate),
+            TicketError::DelegateAlreadyAdded
+        );
+        require!(
+            event.redemption_delegates.len() < Event::MAX_DELEGATES,
+            TicketError::TooManyDelegates
+        );
+        event.redemption_delegates.push(delegate);
+
+        msg!("Delegate {} added to {}", delegate, event.event_id);
+        Ok(())
+    }
+
+    /// Revoke a redemption delegate. Only callable by the organizer.
+    pub fn remove_delegate(ctx: Context<UpdateEvent>, delegate: Pubkey) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        let position = event
+            .redemption_delegates
+            .iter()
+            .position(|key| *key == delegate)
+            .ok_or(TicketError::DelegateNotFound)?;
+        event.redemption_delegates.swap_remove(position);
+
+        msg!("Delegate {} removed from {}", delegate, event.event_id);
+        Ok(())
+    }
+
+    /// Let `co_organizer` take the actions in the `permissions` bitflags
+    /// (`CoOrganizer::CAN_*`). Only callable by the organizer.
+    pub fn add_co_organizer(
+        ctx: Context<UpdateEvent>,
+        co_organizer: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        require!(
+            permissions != 0 && permissions & !CoOrganizer::ALL_PERMISSIONS == 0,
+            TicketError::InvalidPermissions
+        );
+        let event = &mut ctx.accounts.event;
+        require!(
+            !event.co_organizers.iter().any(|co| co.key == co_organizer),
+            TicketError::CoOrganizerAlreadyAdded
+        );
+        require!(
+            event.co_organizers.len() < Event::MAX_CO_ORGANIZERS,
+            TicketError::TooManyCoOrganizers
+        );
+        event.co_organizers.push(CoOrganizer {
+            key: co_organizer,
+            permissions,
+        });
+
+        msg!(
+            "Co-organizer {} added to {} with permissions {:#05b}",
+            co_organizer,
+            event.event_id,
+            permissions
+        );
+        Ok(())
+    }
+
+    /// Revoke a co-organizer. Only callable by the organizer.
+    pub fn remove_co_organizer(ctx: Context<UpdateEvent>, co_organizer: Pubkey) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        let position = event
+            .co_organizers
+            .iter()
+            .position(|co| co.key == co_organizer)
+            .ok_or(TicketError::CoOrganizerNotFound)?;
+        event.co_organizers.swap_remove(position);
+
+        msg!("Co-organizer {} removed from {}", co_organizer, event.event_id);
+        Ok(())
+    }
+
+    /// Move an event through its lifecycle. Only callable by the organizer.
+    /// Allowed: Scheduled -> Live, Live -> Ended, and Scheduled or Live ->
+    /// Cancelled. Ended and Cancelled are terminal. Redemption is only open
+    /// while the event is Live.
+    pub fn set_event_status(ctx: Context<SetEventStatus>, status: EventStatus) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.transition(status)?;
+        if status.is_terminal() {
+            update_profile(&mut ctx.accounts.profile, OrganizerProfile::record_event_closed)?;
+        }
+
+        msg!("Event {} is now {:?}", event.event_id, status);
+        Ok(())
+    }
+
+    /// Cancel an event. Callable by the organizer or a co-organizer with
+    /// `CAN_CANCEL`. Sales and proceeds release stop, and every Active ticket
+    /// holder can then `claim_refund` from the event escrow.
+    pub fn cancel_event(ctx: Context<ManageEvent>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.require_permission(&ctx.accounts.authority.key(), CoOrganizer::CAN_CANCEL)?;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+
+        event.transition(EventStatus::Cancelled)?;
+        update_profile(&mut ctx.accounts.profile, OrganizerProfile::record_event_closed)?;
+
+        msg!("Event {} cancelled — refunds are open", event.event_id);
+        Ok(())
+    }
+
+    /// Cancel an event that missed its minimum sales: `tickets_sold` never
+    /// reached `min_tickets` before `confirm_by`. Permissionless. The refund
+    /// policy is cleared, so every holder's `claim_refund` is a full refund.
+    pub fn auto_cancel_undersubscribed(ctx: Context<AutoCancelEvent>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            event.min_tickets > 0 && !event.confirmed,
+            TicketError::MinSalesMet
+        );
+        require!(
+            Clock::get()?.unix_timestamp > event.confirm_by,
+            TicketError::ConfirmDeadlineNotPassed
+        );
+
+        event.transition(EventStatus::Cancelled)?;
+        event.refund_policy = Vec::new();
+        update_profile(&mut ctx.accounts.profile, OrganizerProfile::record_event_closed)?;
+
+        msg!(
+            "Event {} cancelled with {} of {} tickets sold — full refunds are open",
+            event.event_id,
+            event.tickets_sold,
+            event.min_tickets
+        );
+        Ok(())
+    }
+
+    /// Move an event to a new date. Callable by the organizer or a
+    /// co-organizer with `CAN_RESCHEDULE`. The redemption window shifts with
+    /// it, and the first date the event was moved away from is kept in
+    /// `rescheduled_from`.
+    pub fn reschedule_event(ctx: Context<ManageEvent>, new_date: i64) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.require_permission(&ctx.accounts.authority.key(), CoOrganizer::CAN_RESCHEDULE)?;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            event.status != EventStatus::Ended,
+            TicketError::InvalidEventStatus
+        );
+
+        let old_date = event.event_date;
+        let shift = new_date.saturating_sub(old_date);
+        event.redeem_window_start = event.redeem_window_start.saturating_add(shift);
+        event.redeem_window_end = event.redeem_window_end.saturating_add(shift);
+        event.event_date = new_date;
+        if event.rescheduled_from == 0 {
+            event.rescheduled_from = old_date;
+        }
+
+        msg!("Event {} moved from {} to {}", event.event_id, old_date, new_date);
+        emit!(EventRescheduled {
+            event_id: event.event_id.clone(),
+            old_date,
+            new_date,
+        });
+        Ok(())
+    }
+
+    /// Copy the event's current date onto a ticket after a reschedule.
+    /// Permissionless — it only mirrors data from the Event PDA.
+    pub fn sync_ticket_date(ctx: Context<SyncTicketDate>) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event_date = ctx.accounts.event.event_date;
+
+        msg!("Ticket for {} now dated {}", ticket.attendee_name, ticket.event_date);
+        Ok(())
+    }
+
+    /// Create the zero-copy `EventStats` account for an event. Only callable
+    /// by the organizer. Once it exists, purchases, first scans and refunds
+    /// that pass it bump its counters with plain field writes.
+    pub fn init_event_stats(ctx: Context<InitEventStats>) -> Result<()> {
+        let mut stats = ctx.accounts.stats.load_init()?;
+        stats.event = ctx.accounts.event.key();
+        stats.bump = ctx.bumps.stats;
+
+        msg!("Stats account created for {}", ctx.accounts.event.event_id);
+        Ok(())
+    }
+
+    /// Create the signer's `OrganizerProfile`, which keeps running totals
+    /// across their events. Only activity from then on, in instructions
+    /// that are passed the profile, is counted.
+    pub fn init_organizer_profile(ctx: Context<InitOrganizerProfile>) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        profile.organizer = ctx.accounts.organizer.key();
+        profile.total_events = 0;
+        profile.events_active = 0;
+        profile.total_tickets_sold = 0;
+        profile.total_revenue = 0;
+        profile.bump = ctx.bumps.profile;
+
+        msg!("Organizer profile created for {}", profile.organizer);
+        Ok(())
+    }
+
+    /// Create the signer's `ReferralRewards` account, which collects their
+    /// referral rewards across all events until withdrawn.
+    pub fn init_referral_rewards(ctx: Context<InitReferralRewards>) -> Result<()> {
+        let rewards = &mut ctx.accounts.referral_rewards;
+        rewards.referrer = ctx.accounts.referrer.key();
+        rewards.earned = 0;
+        rewards.withdrawn = 0;
+        rewards.bump = ctx.bumps.referral_rewards;
+
+        msg!("Referral rewards account created for {}", rewards.referrer);
+        Ok(())
+    }
+
+    /// Withdraw every unclaimed referral reward to the referrer.
+    pub fn withdraw_referral_rewards(ctx: Context<WithdrawReferralRewards>) -> Result<()> {
+        let rewards = &mut ctx.accounts.referral_rewards;
+        let amount = math::sub(rewards.earned, rewards.withdrawn)?;
+        require!(amount > 0, TicketError::NoReferralRewards);
+        rewards.withdrawn = rewards.earned;
+        move_lamports(
+            &rewards.to_account_info(),
+            &ctx.accounts.referrer.to_account_info(),
+            amount,
+        )?;
+
+        msg!("{} lamports of referral rewards withdrawn", amount);
+        Ok(())
+    }
+
+    /// Configure the presale phase. Only callable by the organizer.
+    /// While `presale_open` is set, only buyers proven against
+    /// `allowlist_root` can purchase, via `purchase_ticket_presale`.
+    /// Opening a presale without a root fails with `AllowlistRootRequired`.
+    pub fn set_presale(
+        ctx: Context<UpdateEvent>,
+        allowlist_root: Option<[u8; 32]>,
+        presale_open: bool,
+    ) -> Result<()> {
+        require!(
+            !presale_open || allowlist_root.is_some(),
+            TicketError::AllowlistRootRequired
+        );
+
+        let event = &mut ctx.accounts.event;
+        event.allowlist_root = allowlist_root;
+        event.presale_open = presale_open;
+
+        msg!(
+            "Presale for {} is {}",
+            event.event_id,
+            if presale_open { "open" } else { "closed" }
+        );
+        Ok(())
+    }
+
+    /// Purchase and mint a ticket atomically.
+    /// Transfers SOL from buyer into the event escrow (less the platform fee,
+    /// which goes to the Config treasury and is non-refundable), then creates a
+    /// ticket PDA.
+    /// For free events, set price to 0 and payment is skipped.
+    /// Each purchase counts against the Event PDA's capacity for its tier,
+    /// and the price must match that tier's configured price. In a Dutch
+    /// auction `price_lamports` is the most the buyer will pay, and the
+    /// current auction price is charged.
+    /// Events with payout splits pay the recipients, passed in order as
+    /// writable `remaining_accounts`, instead of the escrow.
+    /// Rejected while the event is in its presale phase.
+    /// With `insured`, the buyer also pays the event's insurance premium into
+    /// its insurance pool, passed as `insurance_pool`.
+    /// With a `referrer` (never the buyer), part of the platform fee goes to
+    /// their `ReferralRewards`, passed as `referral_rewards`.
+    /// `accepted_terms_hash` must be the event's `terms_hash` if it has one;
+    /// the same goes for every other purchase path.
+    /// On an event gated by `set_attestation_requirement`, pass the buyer's
+    /// `Attestation` from the event's attester as `attestation`; every SOL
+    /// purchase path fails with `AttestationRequired` without a current,
+    /// positive one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn purchase_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseTicket<'info>>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        price_lamports: u64,
+        cnft_asset_id: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        insured: bool,
+        referrer: Option<Pubkey>,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(!ctx.accounts.event.presale_open, TicketError::PresaleOnly);
+
+        process_purchase(
+            ctx,
+            PurchaseParams {
+                event_id,
+                event_name,
+                attendee_name,
+                seat,
+                price_lamports,
+                cnft_asset_id,
+                tier,
+                transferable,
+                metadata_uri,
+                priced_on_chain: false,
+                insured,
+                referrer,
+                accepted_terms: accepted_terms_hash,
+            },
+        )
+    }
+
+    /// Purchase a ticket during the presale phase.
+    /// Same as `purchase_ticket`, but the buyer must supply a Merkle proof that
+    /// their pubkey is in the event's allowlist.
+    #[allow(clippy::too_many_arguments)]
+    pub fn purchase_ticket_presale<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseTicket<'info>>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        price_lamports: u64,
+        cnft_asset_id: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        proof: Vec<[u8; 32]>,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_feature(Config::FEATURE_PRESALE)?;
+        let event = &ctx.accounts.event;
+        require!(event.presale_open, TicketError::PresaleNotOpen);
+        let root = event.allowlist_root.ok_or(TicketError::NotOnAllowlist)?;
+        require!(
+            verify_merkle_proof(&proof, root, allowlist_leaf(&ctx.accounts.buyer.key())),
+            TicketError::NotOnAllowlist
+        );
+
+        process_purchase(
+            ctx,
+            PurchaseParams {
+                event_id,
+                event_name,
+                attendee_name,
+                seat,
+                price_lamports,
+                cnft_asset_id,
+                tier,
+                transferable,
+                metadata_uri,
+                priced_on_chain: false,
+                insured: false,
+                referrer: None,
+                accepted_terms: accepted_terms_hash,
+            },
+        )
+    }
+
+    /// Purchase a ticket priced in US dollars. The event's `price_usd_cents`
+    /// is converted to lamports with the Pyth SOL/USD price passed as
+    /// `price_update`; stale or low-confidence prices are rejected. The
+    /// buyer caps what they will pay with `max_price_lamports`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn purchase_ticket_usd<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseTicket<'info>>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        max_price_lamports: u64,
+        cnft_asset_id: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_feature(Config::FEATURE_ORACLE_PRICING)?;
+        require!(!ctx.accounts.event.presale_open, TicketError::PresaleOnly);
+        let price_usd_cents = ctx.accounts.event.price_usd_cents;
+        require!(price_usd_cents > 0, TicketError::UsdPricingDisabled);
+        require!(
+            ctx.accounts.event.pricing_mode == PricingMode::Fixed,
+            TicketError::UsdPricingDisabled
+        );
+
+        let price_update = ctx
+            .accounts
+            .price_update
+            .as_ref()
+            .ok_or(TicketError::InvalidOracle)?;
+        let sol_usd = oracle::read_sol_usd(
+            price_update,
+            Clock::get()?.unix_timestamp,
+            MAX_ORACLE_AGE,
+            MAX_ORACLE_CONF_BPS,
+        )?;
+
+        // Converted in one step, so rounding up costs at most one lamport
+        let price_lamports = pyth::usd_cents_to_lamports(price_usd_cents, &sol_usd)?;
+        require!(
+            price_lamports <= max_price_lamports,
+            TicketError::SlippageExceeded
+        );
+        msg!(
+            "{} USD cents = {} lamports at {}e{} USD per SOL",
+            price_usd_cents,
+            price_lamports,
+            sol_usd.price,
+            sol_usd.exponent
+        );
+
+        process_purchase(
+            ctx,
+            PurchaseParams {
+                event_id,
+                event_name,
+                attendee_name,
+                seat,
+                price_lamports,
+                cnft_asset_id,
+                tier,
+                transferable,
+                metadata_uri,
+                priced_on_chain: true,
+                insured: false,
+                referrer: None,
+                accepted_terms: accepted_terms_hash,
+            },
+        )
+    }
+
+    /// Purchase a ticket with a promo code. `code` is the preimage of the
+    /// code hash the organizer registered with `create_promo_code`; the
+    /// tier price is discounted by the code's `discount_bps` and one use is
+    /// counted.
+    #[allow(clippy::too_many_arguments)]
+    pub fn purchase_ticket_with_promo<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseTicket<'info>>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        cnft_asset_id: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        code: Vec<u8>,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(!ctx.accounts.event.presale_open, TicketError::PresaleOnly);
+        let event_key = ctx.accounts.event.key();
+        let list_price = ctx
+            .accounts
+            .event
+            .current_price(tier, Clock::get()?.unix_timestamp)?;
+
+        let promo = ctx
+            .accounts
+            .promo
+            .as_mut()
+            .ok_or(TicketError::InvalidPromoCode)?;
+        require!(
+            promo.event == event_key && promo.code_hash == hash(&code).to_bytes(),
+            TicketError::InvalidPromoCode
+        );
+        require!(promo.uses < promo.max_uses, TicketError::PromoExhausted);
+        promo.uses += 1;
+
+        let discount = math::fee_of(list_price, promo.discount_bps)?;
+        let price_lamports = math::sub(list_price, discount)?;
+        msg!("Promo code applied: {} lamports off", discount);
+
+        process_purchase(
+            ctx,
+            PurchaseParams {
+                event_id,
+                event_name,
+                attendee_name,
+                seat,
+                price_lamports,
+                cnft_asset_id,
+                tier,
+                transferable,
+                metadata_uri,
+                priced_on_chain: true,
+                insured: false,
+                referrer: None,
+                accepted_terms: accepted_terms_hash,
+            },
+        )
+    }
+
+    /// Purchase a ticket at the tier's current price, paid out of the
+    /// buyer's `CreditBalance` (passed as `credits`) instead of their wallet.
+    /// The balance is debited before anything is paid, so it can never be
+    /// spent twice.
+    #[allow(clippy::too_many_arguments)]
+    pub fn purchase_ticket_with_credits<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseTicket<'info>>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        cnft_asset_id: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(!ctx.accounts.event.presale_open, TicketError::PresaleOnly);
+        let price_lamports = ctx
+            .accounts
+            .event
+            .current_price(tier, Clock::get()?.unix_timestamp)?;
+
+        let credits = ctx
+            .accounts
+            .credits
+            .as_mut()
+            .ok_or(TicketError::InsufficientCredits)?;
+        credits.debit(price_lamports)?;
+        // Hand the credits to the buyer for the regular payment path below
+        move_lamports(
+            &credits.to_account_info(),
+            &ctx.accounts.buyer.to_account_info(),
+            price_lamports,
+        )?;
+        msg!(
+            "{} lamports of credits spent, {} left",
+            price_lamports,
+            credits.balance
+        );
+
+        process_purchase(
+            ctx,
+            PurchaseParams {
+                event_id,
+                event_name,
+                attendee_name,
+                seat,
+                price_lamports,
+                cnft_asset_id,
+                tier,
+                transferable,
+                metadata_uri,
+                priced_on_chain: true,
+                insured: false,
+                referrer: None,
+                accepted_terms: accepted_terms_hash,
+            },
+        )
+    }
+
+    /// Load `amount` lamports of credits into the signer's `CreditBalance`,
+    /// creating it on first deposit.
+    pub fn deposit_credits(ctx: Context<DepositCredits>, amount: u64) -> Result<()> {
+        require!(amount > 0, TicketError::InvalidCreditAmount);
+
+        let credits = &mut ctx.accounts.credits;
+        credits.owner = ctx.accounts.owner.key();
+        credits.balance = math::add(credits.balance, amount)?;
+        credits.bump = ctx.bumps.credits;
+        transfer_lamports(
+            &ctx.accounts.system_program,
+            &ctx.accounts.owner.to_account_info(),
+            &credits.to_account_info(),
+            amount,
+        )?;
+
+        msg!("{} lamports of credits deposited, balance {}", amount, credits.balance);
+        Ok(())
+    }
+
+    /// Withdraw `amount` lamports of unused credits back to the owner.
+    pub fn withdraw_credits(ctx: Context<WithdrawCredits>, amount: u64) -> Result<()> {
+        let credits = &mut ctx.accounts.credits;
+        credits.debit(amount)?;
+        move_lamports(
+            &credits.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            amount,
+        )?;
+
+        msg!("{} lamports of credits withdrawn, balance {}", amount, credits.balance);
+        Ok(())
+    }
+
+    /// Purchase up to `MAX_BATCH_SIZE` tickets for one event in a single
+    /// transaction, one per entry in `attendees`. The ticket PDAs are passed,
+    /// in the same order, as writable `remaining_accounts` and are all created
+    /// here; if any already exists the whole purchase fails. Payout split
+    /// recipients, if the event has any, follow the ticket accounts.
+    /// Payment (`price_lamports` per ticket) is taken in one transfer.
+    /// `price_lamports` is the list price; a batch large enough for one of
+    /// the event's `group_discounts` pays, and records as `price_paid`, the
+    /// discounted price.
+    #[allow(clippy::too_many_arguments)]
+    pub fn batch_purchase<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchPurchase<'info>>,
+        event_id: String,
+        event_name: String,
+        attendees: Vec<AttendeeInfo>,
+        price_lamports: u64,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        require!(
+            !attendees.is_empty() && attendees.len() <= MAX_BATCH_SIZE,
+            TicketError::BatchTooLarge
+        );
+        require!(
+            ctx.remaining_accounts.len() == attendees.len() + ctx.accounts.event.payout_splits.len(),
+            TicketError::TicketAccountMismatch
+        );
+        require!(!ctx.accounts.event.presale_open, TicketError::PresaleOnly);
+        // Tour events sell per stop, through the single-ticket SOL paths
+        require!(ctx.accounts.event.venue_stops == 0, TicketError::VenueStopRequired);
+        require!(
+            ctx.accounts.event.currency == Currency::Sol,
+            TicketError::CurrencyMismatch
+        );
+        // Gated events sell one attested ticket at a time
+        require!(
+            ctx.accounts.event.required_attestation == 0,
+            TicketError::AttestationRequired
+        );
+        ctx.accounts.event.check_terms(&accepted_terms_hash)?;
+        validate_metadata_uri(&metadata_uri)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let buyer_key = ctx.accounts.buyer.key();
+        let event = &mut ctx.accounts.event;
+        let (event_date, venue) = (event.event_date, event.venue.clone());
+        event.check_sales_window(now)?;
+        let price_lamports = event.clearing_price(tier, price_lamports, now)?;
+        for attendee in &attendees {
+            Ticket::validate_fields(
+                &event_id,
+                &event_name,
+                &venue,
+                &attendee.attendee_name,
+                &attendee.seat,
+            )?;
+            event.record_sale(tier, price_lamports, now)?;
+            ctx.accounts
+                .allocation
+                .record_purchase(event.max_per_buyer, ctx.bumps.allocation)?;
+        }
+
+        // One transfer for the whole batch, splitting off the platform fee
+        let count = attendees.len() as u64;
+        let price_lamports = ctx
+            .accounts
+            .event
+            .group_price(price_lamports, attendees.len())?;
+        let total = math::mul(price_lamports, count)?;
+        if total > 0 {
+            // A venue paying itself would hide accounting bugs behind a no-op transfer
+            require_keys_neq!(
+                ctx.accounts.buyer.key(),
+                ctx.accounts.event.venue_authority,
+                TicketError::BuyerIsVenue
+            );
+            require!(
+                ctx.accounts.buyer.lamports() >= total,
+                TicketError::InsufficientFunds
+            );
+        }
+        ctx.accounts.event.record_revenue(total)?;
+        let platform_fee = math::fee_of(price_lamports, ctx.accounts.config.platform_fee_bps)?;
+        let total_fee = math::mul(platform_fee, count)?;
+        let venue_share = math::sub(total, total_fee)?;
+        let system_program = &ctx.accounts.system_program;
+        let buyer = ctx.accounts.buyer.to_account_info();
+        if total_fee > 0 {
+            transfer_lamports(system_program, &buyer, &ctx.accounts.fee_destination, total_fee)?;
+        }
+        let (ticket_accounts, split_accounts) = ctx.remaining_accounts.split_at(attendees.len());
+        pay_venue_share(
+            system_program,
+            &buyer,
+            &ctx.accounts.escrow,
+            &mut ctx.accounts.event,
+            split_accounts,
+            venue_share,
+        )?;
+
+        let venue_authority = ctx.accounts.event.venue_authority;
+        let venue_verified = ctx.accounts.event.venue_verified;
+        for (info, attendee) in ticket_accounts.iter().zip(attendees) {
+            let (expected, bump) = ticket_pda(
+                &event_id,
+                &attendee.attendee_name,
+                attendee.seat.as_deref(),
+                &buyer_key,
+            );
+            require_keys_eq!(info.key(), expected, TicketError::TicketAccountMismatch);
+
+            let ticket = Ticket {
+                layout: Ticket::LAYOUT_TAG,
+                event_id: FixedStr::try_from(event_id.as_str())?,
+                event_name: FixedStr::try_from(event_name.as_str())?,
+                event_date,
+                venue: FixedStr::try_from(venue.as_str())?,
+                attendee_name: FixedStr::try_from(attendee.attendee_name)?,
+                seat: attendee.seat.as_deref().map(FixedStr::try_from).transpose()?,
+                venue_authority,
+                price_paid: price_lamports,
+                platform_fee,
+                tier,
+                transferable,
+                metadata_uri: metadata_uri.clone(),
+                redeem_nonce: 0,
+                scan_count: 0,
+                last_scanned_at: 0,
+                claim_hash: [0u8; 32],
+                claim_deadline: 0,
+                payment_mint: Pubkey::default(),
+                ticket_status: TicketStatus::Active,
+                cnft_asset_id: Pubkey::default(),
+                owner: buyer_key,
+                created_at: now,
+                bump,
+                transfer_history: Vec::new(),
+                transfer_count: 0,
+                version: Ticket::CURRENT_VERSION,
+                insured: false,
+                poap_asset_id: Pubkey::default(),
+                frozen: false,
+                venue_verified,
+                reserved_until: 0,
+                refunded_total: 0,
+                accepted_terms: accepted_terms_hash,
+                day_checked_in: 0,
+                original_buyer: buyer_key,
+                delegate: None,
+                delegate_expiry: 0,
+                cancel_reason: None,
+                quantity: 1,
+            };
+            init_ticket_account(system_program, &buyer, info, &ticket)?;
+
+            emit!(TicketPurchased {
+                ticket: expected,
+                owner: buyer_key,
+                event_id: ticket.event_id.to_string(),
+                price_paid: price_lamports,
+            });
+        }
+        for _ in 0..count {
+            update_stats(&ctx.accounts.stats, |stats| stats.record_sale(price_lamports))?;
+            update_profile(&mut ctx.accounts.profile, |profile| {
+                profile.record_sale(price_lamports)
+            })?;
+        }
+
+        msg!("Batch of {} tickets purchased for {} lamports", count, total);
+        Ok(())
+    }
+
+    /// Purchase and mint a ticket paid in an SPL token (e.g. USDC), under
+    /// either the legacy Token program or Token-2022. `mint` must be the
+    /// event's `currency`. Transfers `price_amount`
+    /// base units from the buyer's token account to the venue's token
+    /// account, then creates the same ticket PDA. For mints with a transfer
+    /// fee, `price_paid` records what the venue actually received.
+    #[allow(clippy::too_many_arguments)]
+    pub fn purchase_ticket_spl(
+        ctx: Context<PurchaseTicketSpl>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        price_amount: u64,
+        cnft_asset_id: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        accepted_terms_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(!ctx.accounts.event.presale_open, TicketError::PresaleOnly);
+        // Tour events sell per stop, through the single-ticket SOL paths
+        require!(ctx.accounts.event.venue_stops == 0, TicketError::VenueStopRequired);
+        let venue = ctx.accounts.event.venue.clone();
+        Ticket::validate_fields(&event_id, &event_name, &venue, &attendee_name, &seat)?;
+        validate_metadata_uri(&metadata_uri)?;
+        let currency_mint = ctx.accounts.event.currency.mint();
+        require!(
+            currency_mint != Pubkey::default() && currency_mint == ctx.accounts.mint.key(),
+            TicketError::CurrencyMismatch
+        );
+        // Gated events sell through the attested SOL paths only
+        require!(
+            ctx.accounts.event.required_attestation == 0,
+            TicketError::AttestationRequired
+        );
+        ctx.accounts.event.check_terms(&accepted_terms_hash)?;
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.event.check_sales_window(now)?;
+        let price_amount = ctx.accounts.event.clearing_price(tier, price_amount, now)?;
+        ctx.accounts.event.record_sale(tier, price_amount, now)?;
+        ctx.accounts.event.record_revenue(price_amount)?;
+        ctx.accounts
+            .allocation
+            .record_purchase(ctx.accounts.event.max_per_buyer, ctx.bumps.allocation)?;
+
+        let token_program = ctx.accounts.token_program.key();
+        require!(
+            token_program == TOKEN_PROGRAM_ID || token_program == spl_token_2022::ID,
+            TicketError::UnexpectedTokenProgram
+        );
+        require_keys_eq!(
+            *ctx.accounts.mint.to_account_info().owner,
+            token_program,
+            TicketError::UnexpectedTokenProgram
+        );
+
+        let mut received = price_amount;
+        if price_amount > 0 {
+            // A venue paying itself would hide accounting bugs behind a no-op transfer
+            require_keys_neq!(
+                ctx.accounts.buyer.key(),
+                ctx.accounts.venue_token_account.owner,
+                TicketError::BuyerIsVenue
+            );
+            require!(
+                ctx.accounts.buyer_token_account.amount >= price_amount,
+                TicketError::InsufficientFunds
+            );
+            let fee = transfer_fee_of(&ctx.accounts.mint.to_account_info(), price_amount)?;
+            received = math::sub(price_amount, fee)?;
+            let balance_before = ctx.accounts.venue_token_account.amount;
+
+            let cpi_context = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.buyer_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.venue_token_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            );
+            token_interface::transfer_checked(cpi_context, price_amount, ctx.accounts.mint.decimals)?;
+
+            ctx.accounts.venue_token_account.reload()?;
+            require!(
+                ctx.accounts.venue_token_account.amount == math::add(balance_before, received)?,
+                TicketError::TransferAmountMismatch
+            );
+            msg!(
+                "Payment of {} tokens ({}) transferred to venue, {} withheld as transfer fee",
+                received,
+                ctx.accounts.mint.key(),
+                fee
+            );
+        } else {
+            msg!("Free event — no payment required");
+        }
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event_id = FixedStr::try_from(event_id)?;
+        ticket.event_name = FixedStr::try_from(event_name)?;
+        ticket.event_date = ctx.accounts.event.event_date;
+        ticket.venue = FixedStr::try_from(venue)?;
+        ticket.attendee_name = FixedStr::try_from(attendee_name)?;
+        ticket.seat = seat.as_deref().map(FixedStr::try_from).transpose()?;
+        ticket.price_paid = received;
+        ticket.platform_fee = 0;
+        ticket.tier = tier;
+        ticket.transferable = transferable;
+        ticket.metadata_uri = metadata_uri;
+        ticket.payment_mint = ctx.accounts.mint.key();
+        ticket.venue_authority = ctx.accounts.venue_token_account.owner;
+        ticket.ticket_status = TicketStatus::Active;
+        ticket.cnft_asset_id = cnft_asset_id;
+        ticket.owner = ctx.accounts.buyer.key();
+        ticket.original_buyer = ticket.owner;
+        ticket.created_at = Clock::get()?.unix_timestamp;
+        ticket.bump = ctx.bumps.ticket;
+        ticket.layout = Ticket::LAYOUT_TAG;
+        ticket.version = Ticket::CURRENT_VERSION;
+        ticket.venue_verified = ctx.accounts.event.venue_verified;
+        ticket.quantity = 1;
+        return_rent_surplus(&ticket.to_account_info(), &ctx.accounts.buyer.to_account_info())?;
+        ticket.accepted_terms = accepted_terms_hash;
+        // Revenue totals are in lamports, so a token sale only counts the ticket
+        update_stats(&ctx.accounts.stats, |stats| stats.record_sale(0))?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_sale(0)
+        })?;
+
+        msg!("Ticket created for {} — Status: Active", ticket.attendee_name);
+        emit!(TicketPurchased {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            event_id: ticket.event_id.to_string(),
+            price_paid: ticket.price_paid,
+        });
+        Ok(())
+    }
+
+    /// Redeem a ticket at the venue entrance.
+    /// Only callable by the venue authority or a redemption delegate, while
+    /// the event is Live and within its redemption window.
+    /// Marks ticket as Redeemed, preventing reuse unless the event allows
+    /// re-entry, in which case every scan is counted on the ticket.
+    /// On events with a POAP tree, `mint_poap` then mints an attendance cNFT;
+    /// it is a separate instruction so a failed mint can't turn anyone away.
+    /// On a multi-day event the ticket admits once on each day, with
+    /// `day_index` (0-based, below `days`) naming the day being checked in;
+    /// single-day events take 0.
+    pub fn redeem_ticket(ctx: Context<RedeemTicket>, day_index: u8) -> Result<()> {
+        let first_scan = ctx.accounts.ticket.ticket_status == TicketStatus::Active;
+        redeem(&mut ctx.accounts.ticket, &mut ctx.accounts.event, day_index)?;
+        if first_scan {
+            update_stats(&ctx.accounts.stats, EventStats::record_redemption)?;
+        }
+        Ok(())
+    }
+
+    /// Mint the event's proof-of-attendance cNFT to the holder of a Redeemed
+    /// ticket and record its asset id. Anyone can pay for the mint, once per
+    /// ticket. Send it in its own transaction, after `redeem_ticket`: a
+    /// failed mint then leaves the redemption in place.
+    pub fn mint_poap(ctx: Context<MintPoap>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        require!(
+            accounts.event.poap_tree != Pubkey::default(),
+            TicketError::PoapsNotEnabled
+        );
+        require!(
+            accounts.ticket.ticket_status == TicketStatus::Redeemed,
+            TicketError::DidNotAttend
+        );
+        require!(
+            accounts.ticket.poap_asset_id == Pubkey::default(),
+            TicketError::PoapAlreadyMinted
+        );
+        let (num_minted, capacity) = bubblegum::tree_usage(&accounts.poap_tree_config)
+            .ok_or(TicketError::InvalidTreeConfig)?;
+        require!(num_minted < capacity, TicketError::CnftTreeFull);
+
+        let event_key = accounts.event.key();
+        let seeds: &[&[u8]] = &[b"poap", event_key.as_ref(), &[ctx.bumps.poap_authority]];
+        let name: String = format!("POAP: {}", accounts.ticket.event_name)
+            .chars()
+            .take(32)
+            .collect();
+        bubblegum::mint(
+            bubblegum::MintAccounts {
+                bubblegum_program: &accounts.bubblegum_program,
+                tree_config: &accounts.poap_tree_config,
+                leaf_owner: &accounts.poap_owner,
+                leaf_delegate: &accounts.poap_owner,
+                merkle_tree: &accounts.poap_tree,
+                payer: &accounts.payer,
+                tree_delegate: &accounts.poap_authority,
+                log_wrapper: &accounts.log_wrapper,
+                compression_program: &accounts.compression_program,
+                system_program: &accounts.system_program,
+            },
+            &name,
+            "POAP",
+            &accounts.ticket.metadata_uri,
+            &[seeds],
+        )?;
+
+        let asset_id = bubblegum::asset_id(&accounts.poap_tree.key(), num_minted);
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.poap_asset_id = asset_id;
+        msg!("POAP {} minted to {}", asset_id, ticket.owner);
+        Ok(())
+    }
+
+    /// Redeem a group's tickets, passed as writable `remaining_accounts`
+    /// (at most `MAX_BATCH_REDEEM`), under one venue authority signature.
+    /// Tickets already redeemed (on a multi-day event, already checked in
+    /// today) are skipped and reported with a `RedemptionSkipped` event;
+    /// any other failure fails the whole batch.
+    pub fn batch_redeem<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchRedeem<'info>>,
+        event_id: String,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.len() <= MAX_BATCH_REDEEM,
+            TicketError::RedeemBatchTooLarge
+        );
+
+        let signer = ctx.accounts.venue_authority.key();
+        let now = Clock::get()?.unix_timestamp;
+        let day = ctx.accounts.event.day_of(now);
+        let mut redeemed = 0u32;
+        for info in ctx.remaining_accounts {
+            let mut ticket = Account::<Ticket>::try_from(info)?;
+            require!(ticket.event_id == event_id, TicketError::TicketEventMismatch);
+            require!(
+                ctx.accounts
+                    .event
+                    .is_redeemer(&ticket.venue_authority, &signer),
+                TicketError::UnauthorizedRedemption
+            );
+            if ticket.is_checked_in(&ctx.accounts.event, day) {
+                emit!(RedemptionSkipped {
+                    ticket: ticket.key(),
+                    timestamp: now,
+                });
+                continue;
+            }
+
+            let first_scan = ticket.ticket_status == TicketStatus::Active;
+            redeem(&mut ticket, &mut ctx.accounts.event, day)?;
+            ticket.exit(&crate::ID)?;
+            if first_scan {
+                update_stats(&ctx.accounts.stats, EventStats::record_redemption)?;
+            }
+            redeemed += 1;
+        }
+
+        msg!(
+            "Group entry: {} of {} tickets redeemed",
+            redeemed,
+            ctx.remaining_accounts.len()
+        );
+        Ok(())
+    }
+
+    /// Redeem a ticket and, when `burn` carries the cNFT leaf data, burn the
+    /// ticket's compressed NFT via Bubblegum so it can't be shown at another
+    /// gate. Pass `None` to keep the collectible; the cNFT accounts may then
+    /// be omitted.
+    pub fn redeem_and_burn<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemAndBurn<'info>>,
+        burn: Option<CnftLeaf>,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        let day = ctx.accounts.event.day_of(Clock::get()?.unix_timestamp);
+        let first_scan = ctx.accounts.ticket.ticket_status == TicketStatus::Active;
+        redeem(&mut ctx.accounts.ticket, &mut ctx.accounts.event, day)?;
+        if first_scan {
+            update_stats(&ctx.accounts.stats, EventStats::record_redemption)?;
+        }
+
+        let Some(leaf) = burn else {
+            msg!("cNFT kept as a collectible");
+            return Ok(());
+        };
+
+        let accounts = &ctx.accounts;
+        let (
+            Some(bubblegum_program),
+            Some(tree_config),
+            Some(leaf_owner),
+            Some(leaf_delegate),
+            Some(merkle_tree),
+            Some(log_wrapper),
+            Some(compression_program),
+        ) = (
+            &accounts.bubblegum_program,
+            &accounts.tree_config,
+            &accounts.leaf_owner,
+            &accounts.leaf_delegate,
+            &accounts.merkle_tree,
+            &accounts.log_wrapper,
+            &accounts.compression_program,
+        )
+        else {
+            return err!(TicketError::MissingCnftAccounts);
+        };
+
+        require_keys_eq!(
+            bubblegum::asset_id(&merkle_tree.key(), leaf.nonce),
+            accounts.ticket.cnft_asset_id,
+            TicketError::CnftMismatch
+        );
+
+        bubblegum::burn(
+            bubblegum::BurnAccounts {
+                bubblegum_program,
+                tree_config,
+                leaf_owner,
+                leaf_delegate,
+                merkle_tree,
+                log_wrapper,
+                compression_program,
+                system_program: &accounts.system_program,
+                proof: ctx.remaining_accounts,
+            },
+            &leaf,
+        )?;
+
+        msg!("cNFT {} burned", accounts.ticket.cnft_asset_id);
+        Ok(())
+    }
+
+    /// Issue a season pass to `holder`, valid once at each of `event_ids`.
+    /// Only callable by the organizer; every listed event must also be
+    /// theirs, which `redeem_pass` checks at the gate.
+    pub fn issue_season_pass(
+        ctx: Context<IssueSeasonPass>,
+        pass_id: String,
+        holder: Pubkey,
+        event_ids: Vec<String>,
+    ) -> Result<()> {
+        check_len("pass_id", &pass_id, SeasonPass::MAX_PASS_ID_LEN)?;
+        require!(
+            !event_ids.is_empty() && event_ids.len() <= SeasonPass::MAX_EVENTS,
+            TicketError::InvalidSeasonPass
+        );
+        let mut events: Vec<[u8; 32]> = Vec::with_capacity(event_ids.len());
+        for event_id in &event_ids {
+            let event_hash = seed_hash(event_id);
+            require!(!events.contains(&event_hash), TicketError::InvalidSeasonPass);
+            events.push(event_hash);
+        }
+
+        let pass = &mut ctx.accounts.pass;
+        pass.organizer = ctx.accounts.organizer.key();
+        pass.holder = holder;
+        pass.pass_id = FixedStr::try_from(pass_id)?;
+        pass.events = events;
+        pass.redeemed_events = Vec::new();
+        pass.created_at = Clock::get()?.unix_timestamp;
+        pass.bump = ctx.bumps.pass;
+
+        msg!(
+            "Season pass {} issued to {} for {} events",
+            pass.pass_id,
+            holder,
+            pass.events.len()
+        );
+        Ok(())
+    }
+
+    /// Admit a season pass holder to one of the pass's events. Only callable
+    /// by the event's venue authority or a redemption delegate, under the
+    /// same Live and redemption window rules as tickets. Each event admits
+    /// the pass once.
+    pub fn redeem_pass(ctx: Context<RedeemPass>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            event.status == EventStatus::Live,
+            TicketError::InvalidEventStatus
+        );
+        let now = Clock::get()?.unix_timestamp;
+        event.check_redemption_window(now)?;
+
+        let pass = &mut ctx.accounts.pass;
+        let event_hash = seed_hash(&event.event_id);
+        require!(
+            pass.events.contains(&event_hash),
+            TicketError::PassNotValidForEvent
+        );
+        require!(
+            !pass.redeemed_events.contains(&event_hash),
+            TicketError::PassAlreadyUsed
+        );
+        pass.redeemed_events.push(event_hash);
+
+        msg!("Season pass {} admitted to {}", pass.pass_id, event.event_id);
+        emit!(PassRedeemed {
+            pass: pass.key(),
+            event_id: event.event_id.clone(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Redeem a ticket from an offline scan. The venue authority signs
+    /// `redeem_message(ticket, redeem_nonce)` on the scanner; anyone can then
+    /// submit it in a transaction whose previous instruction is the Ed25519
+    /// precompile check of that signature. The nonce advances on success, so
+    /// a replayed signature fails with `NonceMismatch`.
+    pub fn redeem_with_signature(ctx: Context<RedeemWithSignature>) -> Result<()> {
+        let (signer, message) = ed25519::verified_message(&ctx.accounts.instructions)?;
+        let ticket = &mut ctx.accounts.ticket;
+
+        require!(
+            ctx.accounts
+                .event
+                .is_redeemer(&ticket.venue_authority, &signer),
+            TicketError::UnauthorizedRedemption
+        );
+        require!(
+            message.len() == 40 && message[..32] == ticket.key().to_bytes(),
+            TicketError::InvalidSignature
+        );
+        let signed_nonce = u64::from_le_bytes(message[32..].try_into().unwrap());
+        require!(
+            signed_nonce == ticket.redeem_nonce,
+            TicketError::NonceMismatch
+        );
+
+        let day = ctx.accounts.event.day_of(Clock::get()?.unix_timestamp);
+        let first_scan = ticket.ticket_status == TicketStatus::Active;
+        redeem(ticket, &mut ctx.accounts.event, day)?;
+        ticket.redeem_nonce = ticket
+            .redeem_nonce
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        if first_scan {
+            update_stats(&ctx.accounts.stats, EventStats::record_redemption)?;
+        }
+        Ok(())
+    }
+
+    /// Store a gate pass for scanning with no connectivity. The venue
+    /// authority, or a redemption delegate, signs
+    /// `gate_pass_message(ticket, owner)` ahead of the event; anyone can
+    /// submit it in a transaction whose previous instruction is the Ed25519
+    /// precompile check of that signature. The holder's wallet shows the
+    /// stored signature, which scanners verify offline against the venue
+    /// key; `settle_gate_passes` records the scans once back online.
+    /// A pass can be reissued, e.g. after a transfer, until it is settled.
+    pub fn issue_gate_pass(ctx: Context<IssueGatePass>) -> Result<()> {
+        let (signer, message, signature) =
+            ed25519::verified_signature(&ctx.accounts.instructions)?;
+        let ticket = &ctx.accounts.ticket;
+        require!(
+            ctx.accounts
+                .event
+                .is_redeemer(&ticket.venue_authority, &signer),
+            TicketError::UnauthorizedRedemption
+        );
+        require!(
+            message[..] == gate_pass_message(&ticket.key(), &ticket.owner)[..],
+            TicketError::InvalidSignature
+        );
+        require!(
+            ticket.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!ticket.frozen, TicketError::TicketFrozen);
+
+        let pass = &mut ctx.accounts.gate_pass;
+        require!(pass.scanned_at == 0, TicketError::GatePassSettled);
+        pass.ticket = ticket.key();
+        pass.holder = ticket.owner;
+        pass.signer = signer;
+        pass.signature = signature;
+        pass.issued_at = Clock::get()?.unix_timestamp;
+        pass.scanner = Pubkey::default();
+        pass.conflicts = 0;
+        pass.bump = ctx.bumps.gate_pass;
+
+        msg!("Gate pass for {} issued to {}", ticket.attendee_name, pass.holder);
+        Ok(())
+    }
+
+    /// Reconcile offline gate-pass scans, one `OfflineScan` per
+    /// (ticket, gate pass) pair passed as writable `remaining_accounts`, at
+    /// most `MAX_BATCH_REDEEM` scans. Callable by the venue authority or a
+    /// redemption delegate once the event is Live or Ended. A pass's first
+    /// scan redeems the ticket as of its `scanned_at`. When a ticket was
+    /// scanned more than once, the earliest scan is kept and each later one
+    /// is counted in `conflicts` and reported with a `GateScanConflict`
+    /// event; the ticket is never redeemed twice.
+    pub fn settle_gate_passes<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleGatePasses<'info>>,
+        event_id: String,
+        scans: Vec<OfflineScan>,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        require!(
+            !scans.is_empty() && scans.len() <= MAX_BATCH_REDEEM,
+            TicketError::RedeemBatchTooLarge
+        );
+        require!(
+            ctx.remaining_accounts.len() == 2 * scans.len(),
+            TicketError::TicketAccountMismatch
+        );
+        let event = &mut ctx.accounts.event;
+        require!(
+            matches!(event.status, EventStatus::Live | EventStatus::Ended),
+            TicketError::InvalidEventStatus
+        );
+
+        let signer = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+        let mut settled = 0u32;
+        for (scan, pair) in scans.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let mut ticket = Account::<Ticket>::try_from(&pair[0])?;
+            let mut pass = Account::<GatePass>::try_from(&pair[1])?;
+            require_keys_eq!(pass.ticket, ticket.key(), TicketError::GatePassMismatch);
+            require!(ticket.event_id == event_id, TicketError::TicketEventMismatch);
+            require!(
+                event.is_redeemer(&ticket.venue_authority, &signer)
+                    && event.is_redeemer(&ticket.venue_authority, &scan.scanner),
+                TicketError::UnauthorizedRedemption
+            );
+            require!(scan.scanned_at <= now, TicketError::InvalidScanTime);
+            event.check_redemption_window(scan.scanned_at)?;
+
+            if pass.scanned_at == 0 && ticket.ticket_status == TicketStatus::Active {
+                require!(!ticket.frozen, TicketError::TicketFrozen);
+                event.unlock(ticket.refundable_amount())?;
+                event.redeemed_count = event
+                    .redeemed_count
+                    .checked_add(1)
+                    .ok_or(TicketError::ArithmeticOverflow)?;
+                ticket.ticket_status = TicketStatus::Redeemed;
+                if event.days > 1 {
+                    ticket.day_checked_in |= 1 << event.day_of(scan.scanned_at);
+                }
+                ticket.scan_count = ticket.scan_count.saturating_add(1);
+                ticket.last_scanned_at = scan.scanned_at;
+                pass.scanned_at = scan.scanned_at;
+                pass.scanner = scan.scanner;
+                update_stats(&ctx.accounts.stats, EventStats::record_redemption)?;
+                settled += 1;
+            } else {
+                // Already admitted through this pass, or some other way
+                if pass.scanned_at == 0 {
+                    pass.scanned_at = ticket.last_scanned_at;
+                }
+                let (kept, flagged) = if scan.scanned_at < pass.scanned_at {
+                    let flagged = OfflineScan {
+                        scanned_at: pass.scanned_at,
+                        scanner: pass.scanner,
+                    };
+                    pass.scanned_at = scan.scanned_at;
+                    pass.scanner = scan.scanner;
+                    (scan.clone(), flagged)
+                } else {
+                    let kept = OfflineScan {
+                        scanned_at: pass.scanned_at,
+                        scanner: pass.scanner,
+                    };
+                    (kept, scan.clone())
+                };
+                pass.conflicts = pass.conflicts.saturating_add(1);
+                emit!(GateScanConflict {
+                    ticket: ticket.key(),
+                    kept_scanned_at: kept.scanned_at,
+                    kept_scanner: kept.scanner,
+                    flagged_scanned_at: flagged.scanned_at,
+                    flagged_scanner: flagged.scanner,
+                });
+            }
+            ticket.exit(&crate::ID)?;
+            pass.exit(&crate::ID)?;
+        }
+
+        msg!(
+            "Settled {} of {} offline scans for {}",
+            settled,
+            scans.len(),
+            event_id
+        );
+        Ok(())
+    }
+
+    /// Transfer a ticket to a new owner.
+    /// Only callable by the current owner or its approved delegate, and only
+    /// while the ticket is Active and was issued as transferable. Events
+    /// enforcing royalties on transfer only allow this to the organizer or
+    /// a `free_transfer_recipients` wallet.
+    pub fn transfer_ticket(ctx: Context<TransferTicket>, new_owner: Pubkey) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        let now = Clock::get()?.unix_timestamp;
+
+        ticket.check_transfer_authority(&ctx.accounts.authority.key(), now)?;
+        ticket.check_transferable()?;
+        require!(
+            ctx.accounts.event.allows_free_transfer_to(&new_owner),
+            TicketError::TransferRequiresSale
+        );
+
+        let previous_owner = ticket.owner;
+        ticket.record_transfer(new_owner, &ctx.accounts.event, now)?;
+
+        msg!(
+            "Ticket for {} transferred from {} to {}",
+            ticket.attendee_name,
+            previous_owner,
+            new_owner
+        );
+        Ok(())
+    }
+
+    /// Resell an Active ticket on the secondary market.
+    /// The buyer pays `resale_price` lamports: the organizer receives the
+    /// event's royalty, the ticket's original buyer its original-buyer
+    /// royalty, and the seller the rest. Ownership moves only after all
+    /// payments succeed. Signed by the owner or its approved delegate; the
+    /// proceeds always go to the owner.
+    pub fn resell_ticket(ctx: Context<ResellTicket>, resale_price: u64) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        let event = &ctx.accounts.event;
+
+        ticket.check_transfer_authority(
+            &ctx.accounts.authority.key(),
+            Clock::get()?.unix_timestamp,
+        )?;
+        ticket.check_transferable()?;
+        event.check_resale_price(resale_price)?;
+
+        let (royalty, original_royalty, seller_share) =
+            event.resale_shares(resale_price, 0, ticket)?;
+
+        let system_program = &ctx.accounts.system_program;
+        let buyer = ctx.accounts.buyer.to_account_info();
+        if royalty > 0 {
+            transfer_lamports(system_program, &buyer, &ctx.accounts.organizer, royalty)?;
+        }
+        if original_royalty > 0 {
+            let original_buyer = original_buyer_account(&ctx.accounts.original_buyer)?;
+            transfer_lamports(system_program, &buyer, &original_buyer, original_royalty)?;
+        }
+        if seller_share > 0 {
+            transfer_lamports(system_program, &buyer, &ctx.accounts.seller, seller_share)?;
+        }
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.record_transfer(
+            ctx.accounts.buyer.key(),
+            &ctx.accounts.event,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        msg!(
+            "Ticket for {} resold for {} lamports ({} royalty) to {}",
+            ticket.attendee_name,
+            resale_price,
+            royalty,
+            ticket.owner
+        );
+        Ok(())
+    }
+
+    /// Let `delegate` transfer or resell the ticket on the owner's behalf
+    /// until `expiry`, e.g. a marketplace that doesn't take custody. Only
+    /// callable by the owner; `None` revokes. The delegate can't redeem,
+    /// refund or list the ticket, and the approval ends with any transfer.
+    pub fn approve_delegate(
+        ctx: Context<ApproveDelegate>,
+        delegate: Option<Pubkey>,
+        expiry: i64,
+    ) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        require!(
+            ticket.owner == ctx.accounts.owner.key(),
+            TicketError::UnauthorizedTransfer
+        );
+        if delegate.is_some() {
+            require!(
+                expiry > Clock::get()?.unix_timestamp,
+                TicketError::DelegateExpired
+            );
+        }
+
+        ticket.delegate = delegate;
+        ticket.delegate_expiry = if delegate.is_some() { expiry } else { 0 };
+
+        match delegate {
+            Some(delegate) => msg!("Ticket delegated to {} until {}", delegate, expiry),
+            None => msg!("Ticket delegate revoked"),
+        }
+        Ok(())
+    }
+
+    /// List an Active, transferable ticket for sale at `price` lamports until
+    /// `expiry`. Only callable by the ticket owner; the price is still capped
+    /// by the event's `max_resale_price`.
+    pub fn list_ticket(ctx: Context<ListTicket>, price: u64, expiry: i64) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        require!(
+            ticket.owner == ctx.accounts.seller.key(),
+            TicketError::UnauthorizedTransfer
+        );
+        ticket.check_transferable()?;
+        ctx.accounts.event.check_resale_price(price)?;
+        require!(
+            expiry > Clock::get()?.unix_timestamp,
+            TicketError::ListingExpired
+        );
+
+        let listing = &mut ctx.accounts.listing;
+        listing.ticket = ticket.key();
+        listing.seller = ticket.owner;
+        listing.price = price;
+        listing.expiry = expiry;
+        listing.bump = ctx.bumps.listing;
+
+        msg!("Ticket listed for {} lamports until {}", price, expiry);
+        Ok(())
+    }
+
+    /// Buy a listed ticket. The buyer pays the listing price: the platform
+    /// fee goes to the Config treasury, the event royalty to the organizer, the
+    /// original-buyer royalty to the ticket's first owner and the rest to the
+    /// seller. Ownership moves and the listing is closed.
+    /// When `remint` carries the cNFT leaf data and the cNFT accounts are
+    /// passed (proof as `remaining_accounts`), the seller's cNFT is burned
+    /// and a fresh one minted to the buyer in the same tree, and
+    /// `cnft_asset_id` is updated. This needs the `[b"cnft_authority"]` PDA
+    /// to be the cNFT's leaf delegate and the tree's delegate; re-minted
+    /// cNFTs are delegated to it, so they stay in sync on later resales.
+    /// Otherwise only ownership moves.
+    pub fn buy_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyListing<'info>>,
+        remint: Option<CnftLeaf>,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        let listing = &ctx.accounts.listing;
+        let ticket = &ctx.accounts.ticket;
+        require!(
+            Clock::get()?.unix_timestamp <= listing.expiry,
+            TicketError::ListingExpired
+        );
+        require!(ticket.owner == listing.seller, TicketError::OwnerMismatch);
+        ticket.check_transferable()?;
+
+        let price = listing.price;
+        ctx.accounts.event.check_resale_price(price)?;
+        let fee = math::fee_of(price, ctx.accounts.config.platform_fee_bps)?;
+        let (royalty, original_royalty, seller_share) =
+            ctx.accounts.event.resale_shares(price, fee, ticket)?;
+
+        let system_program = &ctx.accounts.system_program;
+        let buyer = ctx.accounts.buyer.to_account_info();
+        if fee > 0 {
+            transfer_lamports(system_program, &buyer, &ctx.accounts.fee_destination, fee)?;
+        }
+        if royalty > 0 {
+            transfer_lamports(system_program, &buyer, &ctx.accounts.organizer, royalty)?;
+        }
+        if original_royalty > 0 {
+            let original_buyer = original_buyer_account(&ctx.accounts.original_buyer)?;
+            transfer_lamports(system_program, &buyer, &original_buyer, original_royalty)?;
+        }
+        if seller_share > 0 {
+            transfer_lamports(
+                system_program,
+                &buyer,
+                &ctx.accounts.seller.to_account_info(),
+                seller_share,
+            )?;
+        }
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.record_transfer(
+            ctx.accounts.buyer.key(),
+            &ctx.accounts.event,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        msg!(
+            "Listed ticket for {} bought for {} lamports by {}",
+            ticket.attendee_name,
+            price,
+            ticket.owner
+        );
+        match remint {
+            Some(leaf) => remint_cnft(
+                ctx.accounts,
+                ctx.bumps.cnft_authority,
+                &leaf,
+                ctx.remaining_accounts,
+            ),
+            None => {
+                msg!("cNFT left with the seller");
+                Ok(())
+            }
+        }
+    }
+
+    /// Withdraw a listing. Only callable by the seller; rent is returned.
+    pub fn delist_ticket(ctx: Context<DelistTicket>) -> Result<()> {
+        msg!("Listing for ticket {} withdrawn", ctx.accounts.listing.ticket);
+        Ok(())
+    }
+
+    /// Bid `amount` lamports for a ticket, escrowed in an Offer PDA at
+    /// `[b"offer", ticket, bidder]`. Any number of bidders may hold offers on
+    /// the same ticket at once.
+    pub fn make_offer(ctx: Context<MakeOffer>, amount: u64) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        ticket.check_transferable()?;
+        ctx.accounts.event.check_resale_price(amount)?;
+
+        transfer_lamports(
+            &ctx.accounts.system_program,
+            &ctx.accounts.bidder.to_account_info(),
+            &ctx.accounts.offer.to_account_info(),
+            amount,
+        )?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.ticket = ticket.key();
+        offer.bidder = ctx.accounts.bidder.key();
+        offer.amount = amount;
+        offer.bump = ctx.bumps.offer;
+
+        msg!("Offer of {} lamports made by {}", amount, offer.bidder);
+        Ok(())
+    }
+
+    /// Accept an offer. Only callable by the ticket's current owner, checked
+    /// at acceptance time in case the ticket changed hands after the bid.
+    /// The escrow pays the platform fee, both royalties and the seller;
+    /// the offer is closed and its rent returned to the bidder.
+    pub fn accept_offer(ctx: Context<AcceptOffer>) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        require!(
+            ticket.owner == ctx.accounts.seller.key(),
+            TicketError::UnauthorizedTransfer
+        );
+        ticket.check_transferable()?;
+
+        let amount = ctx.accounts.offer.amount;
+        ctx.accounts.event.check_resale_price(amount)?;
+        let fee = math::fee_of(amount, ctx.accounts.config.platform_fee_bps)?;
+        let (royalty, original_royalty, seller_share) =
+            ctx.accounts.event.resale_shares(amount, fee, ticket)?;
+
+        let offer = ctx.accounts.offer.to_account_info();
+        move_lamports(&offer, &ctx.accounts.fee_destination, fee)?;
+        move_lamports(&offer, &ctx.accounts.organizer, royalty)?;
+        if original_royalty > 0 {
+            let original_buyer = original_buyer_account(&ctx.accounts.original_buyer)?;
+            move_lamports(&offer, &original_buyer, original_royalty)?;
+        }
+        move_lamports(&offer, &ctx.accounts.seller.to_account_info(), seller_share)?;
+
+        let bidder = ctx.accounts.offer.bidder;
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.record_transfer(bidder, &ctx.accounts.event, Clock::get()?.unix_timestamp)?;
+
+        msg!(
+            "Offer of {} lamports accepted — ticket for {} now owned by {}",
+            amount,
+            ticket.attendee_name,
+            ticket.owner
+        );
+        Ok(())
+    }
+
+    /// Withdraw an offer. Only callable by the bidder; the escrowed lamports
+    /// and rent are returned.
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        msg!(
+            "Offer of {} lamports withdrawn by {}",
+            ctx.accounts.offer.amount,
+            ctx.accounts.bidder.key()
+        );
+        Ok(())
+    }
+
+    /// Open a dispute over a ticket (e.g. fraudulent resale, denied entry)
+    /// for the arbiter to resolve. Only callable by the ticket's owner, and
+    /// only once per ticket. `reason_code` is an off-chain defined category.
+    /// Tickets already used, marked NoShow or cancelled can't be disputed:
+    /// their escrow has been released or refunded. Nor can unpaid holds,
+    /// which have no escrow to dispute.
+    pub fn open_dispute(ctx: Context<OpenDispute>, reason_code: u8) -> Result<()> {
+        require!(
+            !matches!(
+                ctx.accounts.ticket.ticket_status,
+                TicketStatus::Redeemed
+                    | TicketStatus::NoShow
+                    | TicketStatus::Cancelled
+                    | TicketStatus::Reserved
+            ),
+            TicketError::TicketNotActive
+        );
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.ticket = ctx.accounts.ticket.key();
+        dispute.opener = ctx.accounts.owner.key();
+        dispute.reason_code = reason_code;
+        dispute.status = DisputeStatus::Open;
+        dispute.resolution_code = 0;
+        dispute.opened_at = Clock::get()?.unix_timestamp;
+        dispute.resolved_at = 0;
+        dispute.bump = ctx.bumps.dispute;
+
+        msg!("Dispute opened on {} (reason {})", dispute.ticket, reason_code);
+        Ok(())
+    }
+
+    /// Resolve an open dispute. Only callable by the config arbiter, who
+    /// can only move money along fixed paths: `Refund` returns the ticket's
+    /// escrowed price to its current owner and cancels it, `Reinstate` lifts
+    /// any freeze so the ticket can be used again, and `Reject` leaves
+    /// everything as is, so the escrow is released to the venue as usual.
+    /// `Refund` and `Reinstate` only apply to Active tickets, whose escrow is
+    /// still locked.
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        resolution: DisputeResolution,
+        resolution_code: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.dispute.status == DisputeStatus::Open,
+            TicketError::DisputeAlreadyResolved
+        );
+
+        let ticket = &mut ctx.accounts.ticket;
+        let status = match resolution {
+            DisputeResolution::Refund => {
+                require!(
+                    ticket.ticket_status == TicketStatus::Active,
+                    TicketError::TicketNotActive
+                );
+                let refund = ticket.refundable_amount();
+                if refund > 0 {
+                    require!(
+                        ticket.payment_mint == Pubkey::default(),
+                        TicketError::MintMismatch
+                    );
+                    refund_from_escrow(
+                        &ctx.accounts.system_program,
+                        &ctx.accounts.escrow,
+                        &ctx.accounts.owner,
+                        &ctx.accounts.event,
+                        refund,
+                    )?;
+                }
+                let event = &mut ctx.accounts.event;
+                event.locked = event.locked.saturating_sub(refund);
+                ticket.cancel(CancelReason::Other)?;
+                ticket.frozen = false;
+                event.release_seats(ticket.tier, ticket.admits())?;
+                DisputeStatus::Refunded
+            }
+            DisputeResolution::Reinstate => {
+                require!(
+                    ticket.ticket_status == TicketStatus::Active,
+                    TicketError::TicketNotActive
+                );
+                ticket.frozen = false;
+                DisputeStatus::Reinstated
+            }
+            DisputeResolution::Reject => DisputeStatus::Rejected,
+        };
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.status = status;
+        dispute.resolution_code = resolution_code;
+        dispute.resolved_at = Clock::get()?.unix_timestamp;
+
+        msg!("Dispute on {} resolved: {:?}", dispute.ticket, status);
+        emit!(DisputeResolved {
+            ticket: dispute.ticket,
+            status,
+            resolution_code,
+        });
+        Ok(())
+    }
+
+    /// Freeze an Active ticket, e.g. while a dispute or fraud report is
+    /// investigated. It stays Active but can't be transferred, resold,
+    /// redeemed or refunded until unfrozen. Callable by the organizer or the
+    /// config arbiter.
+    pub fn freeze_ticket(ctx: Context<FreezeTicket>) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        require!(
+            ticket.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        ticket.frozen = true;
+
+        msg!("Ticket {} frozen", ticket.key());
+        emit!(TicketFrozen {
+            ticket: ticket.key(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Lift a freeze. Callable by the organizer or the config arbiter.
+    pub fn unfreeze_ticket(ctx: Context<FreezeTicket>) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.frozen = false;
+
+        msg!("Ticket {} unfrozen", ticket.key());
+        emit!(TicketUnfrozen {
+            ticket: ticket.key(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Allocate a tier by lottery instead of first come, first served. Only
+    /// callable by the organizer. Entrants `register_interest` until
+    /// `registration_end`, escrowing the tier price; the organizer then commits
+    /// Switchboard randomness and anyone can `draw_winners`.
+    pub fn open_lottery(
+        ctx: Context<UpdateEvent>,
+        tier: TicketTier,
+        registration_end: i64,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(event.lottery.is_none(), TicketError::LotteryAlreadyOpen);
+        require!(
+            registration_end > Clock::get()?.unix_timestamp,
+            TicketError::RegistrationClosed
+        );
+
+        event.lottery = Some(Lottery {
+            tier,
+            registration_end,
+            deposit: event.tier_prices[tier.index()],
+            entrant_count: 0,
+            randomness: Pubkey::default(),
+            seed: [0u8; 32],
+            winners: 0,
+            stride: 0,
+            offset: 0,
+            drawn: false,
+        });
+
+        msg!("Lottery for {} open until {}", event.event_id, registration_end);
+        Ok(())
+    }
+
+    /// Enter an event's lottery, escrowing the deposit in an Entry PDA.
+    /// One entry per wallet.
+    pub fn register_interest(ctx: Context<RegisterInterest>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let event_key = ctx.accounts.event.key();
+        let lottery = ctx
+            .accounts
+            .event
+            .lottery
+            .as_mut()
+            .ok_or(TicketError::NoLottery)?;
+        require!(now <= lottery.registration_end, TicketError::RegistrationClosed);
+
+        let entry = &mut ctx.accounts.entry;
+        entry.event = event_key;
+        entry.entrant = ctx.accounts.entrant.key();
+        entry.index = lottery.entrant_count;
+        entry.deposit = lottery.deposit;
+        entry.bump = ctx.bumps.entry;
+        lottery.entrant_count = lottery
+            .entrant_count
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+
+        if entry.deposit > 0 {
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.entrant.to_account_info(),
+                &entry.to_account_info(),
+                entry.deposit,
+            )?;
+        }
+
+        msg!("Lottery entry {} registered for {}", entry.index, entry.entrant);
+        Ok(())
+    }
+
+    /// Commit the Switchboard randomness account the draw will use. Only
+    /// callable by the organizer, once, after registration closes; the
+    /// request must have been made in the previous slot so its value can't
+    /// be known yet.
+    pub fn commit_lottery(ctx: Context<CommitLottery>) -> Result<()> {
+        let now = Clock::get()?;
+        let randomness = switchboard::load_randomness(&ctx.accounts.randomness)?;
+        require!(
+            randomness.seed_slot == now.slot.saturating_sub(1) && randomness.reveal_slot == 0,
+            TicketError::InvalidRandomness
+        );
+
+        let event: &mut Event = &mut ctx.accounts.event;
+        let lottery = event.lottery.as_mut().ok_or(TicketError::NoLottery)?;
+        require!(
+            now.unix_timestamp > lottery.registration_end,
+            TicketError::RegistrationOpen
+        );
+        require!(
+            lottery.randomness == Pubkey::default(),
+            TicketError::LotteryAlreadyDrawn
+        );
+        lottery.randomness = ctx.accounts.randomness.key();
+
+        msg!("Lottery for {} committed to {}", event.event_id, lottery.randomness);
+        Ok(())
+    }
+
+    /// Draw the winners from the revealed randomness. Callable by anyone once
+    /// the committed randomness account is revealed. The result is fully
+    /// determined by the seed, so it can be audited off-chain.
+    pub fn draw_winners(ctx: Context<DrawWinners>) -> Result<()> {
+        let event: &mut Event = &mut ctx.accounts.event;
+        let tier = event.lottery.as_ref().ok_or(TicketError::NoLottery)?.tier;
+        let i = tier.index();
+        let available = event.tier_capacities[i]
+            .saturating_sub(event.tier_sold[i])
+            .min(event.capacity.saturating_sub(event.tickets_sold));
+
+        let lottery = event.lottery.as_mut().ok_or(TicketError::NoLottery)?;
+        require!(!lottery.drawn, TicketError::LotteryAlreadyDrawn);
+        require_keys_eq!(
+            ctx.accounts.randomness.key(),
+            lottery.randomness,
+            TicketError::InvalidRandomness
+        );
+        let randomness = switchboard::load_randomness(&ctx.accounts.randomness)?;
+        require!(randomness.reveal_slot > 0, TicketError::InvalidRandomness);
+
+        lottery.draw(randomness.value, available);
+
+        msg!(
+            "Lottery for {} drawn: {} winners from {} entrants",
+            event.event_id,
+            lottery.winners,
+            lottery.entrant_count
+        );
+        emit!(LotteryDrawn {
+            event_id: event.event_id.clone(),
+            seed: lottery.seed,
+            entrants: lottery.entrant_count,
+            winners: lottery.winners,
+        });
+        Ok(())
+    }
+
+    /// Turn a winning lottery entry into a ticket. The deposit pays for it
+    /// (less the platform fee, as for `purchase_ticket`) and the entry is
+    /// closed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim_lottery_ticket(
+        ctx: Context<ClaimLotteryTicket>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        transferable: bool,
+        metadata_uri: String,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        Ticket::validate_fields(&event_id, &event_name, &event.venue, &attendee_name, &seat)?;
+        validate_metadata_uri(&metadata_uri)?;
+        let lottery = event.lottery.as_ref().ok_or(TicketError::NoLottery)?;
+        require!(lottery.drawn, TicketError::LotteryNotDrawn);
+        require!(
+            lottery.is_winner(ctx.accounts.entry.index),
+            TicketError::NotAWinner
+        );
+        let tier = lottery.tier;
+        event.take_seat(tier)?;
+
+        let deposit = ctx.accounts.entry.deposit;
+        event.record_revenue(deposit)?;
+        let platform_fee = math::fee_of(deposit, ctx.accounts.config.platform_fee_bps)?;
+        let entry = ctx.accounts.entry.to_account_info();
+        move_lamports(&entry, &ctx.accounts.fee_destination, platform_fee)?;
+        let escrowed = math::sub(deposit, platform_fee)?;
+        move_lamports(&entry, &ctx.accounts.escrow.to_account_info(), escrowed)?;
+        event.locked = math::add(event.locked, escrowed)?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event_id = FixedStr::try_from(event_id)?;
+        ticket.event_name = FixedStr::try_from(event_name)?;
+        ticket.event_date = event.event_date;
+        ticket.venue = FixedStr::try_from(event.venue.as_str())?;
+        ticket.attendee_name = FixedStr::try_from(attendee_name)?;
+        ticket.seat = seat.as_deref().map(FixedStr::try_from).transpose()?;
+        ticket.price_paid = deposit;
+        ticket.platform_fee = platform_fee;
+        ticket.tier = tier;
+        ticket.transferable = transferable;
+        ticket.metadata_uri = metadata_uri;
+        ticket.payment_mint = Pubkey::default();
+        ticket.venue_authority = event.venue_authority;
+        ticket.ticket_status = TicketStatus::Active;
+        ticket.cnft_asset_id = Pubkey::default();
+        ticket.owner = ctx.accounts.entrant.key();
+        ticket.original_buyer = ticket.owner;
+        ticket.created_at = Clock::get()?.unix_timestamp;
+        ticket.bump = ctx.bumps.ticket;
+        ticket.layout = Ticket::LAYOUT_TAG;
+        ticket.version = Ticket::CURRENT_VERSION;
+        ticket.venue_verified = event.venue_verified;
+        ticket.quantity = 1;
+        return_rent_surplus(&ticket.to_account_info(), &ctx.accounts.entrant.to_account_info())?;
+        update_stats(&ctx.accounts.stats, |stats| stats.record_sale(deposit))?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_sale(deposit)
+        })?;
+
+        msg!("Lottery ticket claimed for {}", ticket.attendee_name);
+        emit!(TicketPurchased {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            event_id: ticket.event_id.to_string(),
+            price_paid: deposit,
+        });
+        Ok(())
+    }
+
+    /// Close a losing lottery entry and return its deposit and rent. Also
+    /// open to every entrant if the event is cancelled.
+    pub fn refund_entry(ctx: Context<RefundEntry>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        if event.status != EventStatus::Cancelled {
+            let lottery = event.lottery.as_ref().ok_or(TicketError::NoLottery)?;
+            require!(lottery.drawn, TicketError::LotteryNotDrawn);
+            require!(
+                !lottery.is_winner(ctx.accounts.entry.index),
+                TicketError::EntryWon
+            );
+        }
+
+        msg!(
+            "Lottery deposit of {} lamports returned to {}",
+            ctx.accounts.entry.deposit,
+            ctx.accounts.entrant.key()
+        );
+        Ok(())
+    }
+
+    /// Queue for a sold-out event. Escrows the current price of `tier` in a
+    /// `WaitlistEntry` stamped with the event's next sequence number, along
+    /// with the details the promoted ticket will carry.
+    pub fn join_waitlist(
+        ctx: Context<JoinWaitlist>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        tier: TicketTier,
+        metadata_uri: String,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        Ticket::validate_fields(&event_id, &event_name, &event.venue, &attendee_name, &None)?;
+        validate_metadata_uri(&metadata_uri)?;
+        require!(event.venue_stops == 0, TicketError::VenueStopRequired);
+        require!(event.is_sold_out(tier), TicketError::NotSoldOut);
+        let deposit = event.current_price(tier, Clock::get()?.unix_timestamp)?;
+
+        let entry = &mut ctx.accounts.entry;
+        entry.event = event.key();
+        entry.entrant = ctx.accounts.entrant.key();
+        entry.sequence = event.waitlist_next;
+        entry.tier = tier;
+        entry.deposit = deposit;
+        entry.event_name = FixedStr::try_from(event_name)?;
+        entry.attendee_name = FixedStr::try_from(attendee_name)?;
+        entry.metadata_uri = metadata_uri;
+        entry.bump = ctx.bumps.entry;
+        event.waitlist_next = math::add(event.waitlist_next, 1)?;
+
+        if deposit > 0 {
+            transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.entrant.to_account_info(),
+                &entry.to_account_info(),
+                deposit,
+            )?;
+        }
+
+        msg!("Waitlist entry {} joined by {}", entry.sequence, entry.entrant);
+        Ok(())
+    }
+
+    /// Turn the oldest waitlist entry into a ticket once a seat is free
+    /// (e.g. after a cancellation or refund), paying for it with the entry's
+    /// deposit. Permissionless, so anyone can crank the queue; the signer
+    /// pays the ticket's rent. Entries older than the promoted one must
+    /// have left, proven by passing their (now empty) PDAs, in order, as
+    /// `remaining_accounts`.
+    pub fn promote_from_waitlist(ctx: Context<PromoteFromWaitlist>) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        let event_key = ctx.accounts.event.key();
+        let sequence = ctx.accounts.entry.sequence;
+        let head = ctx.accounts.event.waitlist_head;
+        require!(
+            math::add(head, ctx.remaining_accounts.len() as u64)? == sequence,
+            TicketError::WaitlistOutOfOrder
+        );
+        for (skipped, account) in (head..sequence).zip(ctx.remaining_accounts) {
+            let (expected, _) = Pubkey::find_program_address(
+                &[b"waitlist", event_key.as_ref(), &skipped.to_le_bytes()],
+                &crate::ID,
+            );
+            require!(
+                account.key() == expected && account.lamports() == 0,
+                TicketError::WaitlistOutOfOrder
+            );
+        }
+
+        let event = &mut ctx.accounts.event;
+        let entry = &ctx.accounts.entry;
+        event.reserve_seat(entry.tier)?;
+        event.waitlist_head = math::add(sequence, 1)?;
+
+        let deposit = entry.deposit;
+        event.record_revenue(deposit)?;
+        let platform_fee = math::fee_of(deposit, ctx.accounts.config.platform_fee_bps)?;
+        let entry_info = entry.to_account_info();
+        move_lamports(&entry_info, &ctx.accounts.fee_destination, platform_fee)?;
+        let escrowed = math::sub(deposit, platform_fee)?;
+        move_lamports(&entry_info, &ctx.accounts.escrow.to_account_info(), escrowed)?;
+        event.locked = math::add(event.locked, escrowed)?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event_id = FixedStr::try_from(event.event_id.as_str())?;
+        ticket.event_name = entry.event_name;
+        ticket.event_date = event.event_date;
+        ticket.venue = FixedStr::try_from(event.venue.as_str())?;
+        ticket.attendee_name = entry.attendee_name;
+        ticket.seat = None;
+        ticket.price_paid = deposit;
+        ticket.platform_fee = platform_fee;
+        ticket.tier = entry.tier;
+        ticket.transferable = true;
+        ticket.metadata_uri = entry.metadata_uri.clone();
+        ticket.payment_mint = Pubkey::default();
+        ticket.venue_authority = event.venue_authority;
+        ticket.ticket_status = TicketStatus::Active;
+        ticket.cnft_asset_id = Pubkey::default();
+        ticket.owner = entry.entrant;
+        ticket.original_buyer = ticket.owner;
+        ticket.created_at = Clock::get()?.unix_timestamp;
+        ticket.bump = ctx.bumps.ticket;
+        ticket.layout = Ticket::LAYOUT_TAG;
+        ticket.version = Ticket::CURRENT_VERSION;
+        ticket.venue_verified = event.venue_verified;
+        ticket.quantity = 1;
+        return_rent_surplus(&ticket.to_account_info(), &ctx.accounts.payer.to_account_info())?;
+        update_stats(&ctx.accounts.stats, |stats| stats.record_sale(deposit))?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_sale(deposit)
+        })?;
+
+        msg!("Waitlist entry {} promoted for {}", sequence, ticket.attendee_name);
+        emit!(TicketPurchased {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            event_id: ticket.event_id.to_string(),
+            price_paid: deposit,
+        });
+        Ok(())
+    }
+
+    /// Leave the waitlist, closing the entry and returning its deposit and
+    /// rent to the entrant.
+    pub fn leave_waitlist(ctx: Context<LeaveWaitlist>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        let entry = &ctx.accounts.entry;
+        if entry.sequence == event.waitlist_head {
+            event.waitlist_head = math::add(event.waitlist_head, 1)?;
+        }
+
+        msg!(
+            "Waitlist deposit of {} lamports returned to {}",
+            entry.deposit,
+            entry.entrant
+        );
+        Ok(())
+    }
+
+    /// Return an unpromoted waitlist entry's deposit and rent to its entrant
+    /// once the waitlist is over: the event has started or been cancelled.
+    /// Permissionless, since the lamports can only go to the entrant; the
+    /// entry is closed, so it can't be refunded twice. Promoted entries were
+    /// already closed when their ticket was issued.
+    pub fn refund_waitlist_deposit(ctx: Context<RefundWaitlistDeposit>) -> Result<()> {
+        require!(
+            ctx.accounts
+                .event
+                .waitlist_closed(Clock::get()?.unix_timestamp),
+            TicketError::WaitlistStillOpen
+        );
+
+        let entry = &ctx.accounts.entry;
+        msg!(
+            "Waitlist deposit of {} lamports returned to {}",
+            entry.deposit,
+            entry.entrant
+        );
+        emit!(DepositRefunded {
+            entry: entry.key(),
+            entrant: entry.entrant,
+            amount: entry.deposit,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Close many losing deposits in one transaction. Only callable by the
+    /// organizer. `remaining_accounts` holds up to `MAX_BATCH_REFUNDS` pairs
+    /// of (entry, entrant), each entry either a `WaitlistEntry` (once the
+    /// waitlist is over, as for `refund_waitlist_deposit`) or a lottery
+    /// `Entry` that lost the draw (as for `refund_entry`). Each entry's
+    /// deposit and rent go back to its entrant. Winners' entries are
+    /// rejected, and claimed ones no longer exist.
+    pub fn sweep_deposit_refunds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepDepositRefunds<'info>>,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        let accounts = ctx.remaining_accounts;
+        require!(
+            !accounts.is_empty()
+                && accounts.len().is_multiple_of(2)
+                && accounts.len() / 2 <= MAX_BATCH_REFUNDS,
+            TicketError::RefundBatchTooLarge
+        );
+
+        let event = &ctx.accounts.event;
+        let now = Clock::get()?.unix_timestamp;
+        let mut total = 0u64;
+        for pair in accounts.chunks(2) {
+            let (entry_info, entrant) = (&pair[0], &pair[1]);
+            let (entrant_key, deposit) = if let Ok(entry) =
+                Account::<WaitlistEntry>::try_from(entry_info)
+            {
+                require_keys_eq!(entry.event, event.key(), TicketError::TicketEventMismatch);
+                require!(event.waitlist_closed(now), TicketError::WaitlistStillOpen);
+                require_keys_eq!(entrant.key(), entry.entrant, TicketError::OwnerMismatch);
+                let refund = (entry.entrant, entry.deposit);
+                entry.close(entrant.clone())?;
+                refund
+            } else {
+                let entry = Account::<Entry>::try_from(entry_info)?;
+                require_keys_eq!(entry.event, event.key(), TicketError::TicketEventMismatch);
+                if event.status != EventStatus::Cancelled {
+                    let lottery = event.lottery.as_ref().ok_or(TicketError::NoLottery)?;
+                    require!(lottery.drawn, TicketError::LotteryNotDrawn);
+                    require!(!lottery.is_winner(entry.index), TicketError::EntryWon);
+                }
+                require_keys_eq!(entrant.key(), entry.entrant, TicketError::OwnerMismatch);
+                let refund = (entry.entrant, entry.deposit);
+                entry.close(entrant.clone())?;
+                refund
+            };
+            total = math::add(total, deposit)?;
+            emit!(DepositRefunded {
+                entry: entry_info.key(),
+                entrant: entrant_key,
+                amount: deposit,
+                timestamp: now,
+            });
+        }
+
+        msg!(
+            "{} deposits totalling {} lamports refunded",
+            accounts.len() / 2,
+            total
+        );
+        Ok(())
+    }
+
+    /// Cancel a ticket and refund the holder.
+    /// Only callable by the venue authority. Refunds the escrowed part of
+    /// `price_paid` (everything but the platform fee) to the current ticket
+    /// owner, scaled by the event's refund policy; the venue keeps the rest.
+    /// Free tickets skip the transfer. The seat goes back on sale.
+    /// `reason` is recorded on the ticket for support and reporting.
+    pub fn cancel_ticket(ctx: Context<CancelTicket>, reason: CancelReason) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+
+        require!(
+            ticket.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!ticket.frozen, TicketError::TicketFrozen);
+
+        if ticket.refundable_amount() > 0 {
+            // Lamport refunds only — SPL-paid tickets need a token refund path
+            require!(
+                ticket.payment_mint == Pubkey::default(),
+                TicketError::MintMismatch
+            );
+        }
+
+        let refund = settle_refund(
+            &ctx.accounts.system_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.owner,
+            &ctx.accounts.venue.to_account_info(),
+            &mut ctx.accounts.event,
+            ticket.refundable_amount(),
+        )?;
+        if ticket.refundable_amount() > 0 {
+            msg!("Refund of {} lamports sent to {}", refund, ticket.owner);
+        } else {
+            msg!("Free ticket — no refund required");
+        }
+
+        ticket.cancel(reason)?;
+        ctx.accounts.event.release_seats(ticket.tier, ticket.admits())?;
+        update_stats(&ctx.accounts.stats, EventStats::record_refund)?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_refund(ticket.price_paid)
+        })?;
+
+        msg!("Ticket cancelled for {}", ticket.attendee_name);
+        emit!(TicketCancelled {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            refund_amount: refund,
+            reason,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Pay out the event's escrowed proceeds to the venue once the event date
+    /// has passed. The escrow keeps its rent-exempt minimum, and nothing is
+    /// released for a cancelled event.
+    pub fn release_proceeds(ctx: Context<ReleaseProceeds>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            Clock::get()?.unix_timestamp > event.event_date,
+            TicketError::EventNotOver
+        );
+
+        let amount = escrow_available(&ctx.accounts.escrow)?;
+        if amount > 0 {
+            transfer_from_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.escrow,
+                &ctx.accounts.venue,
+                event,
+                amount,
+            )?;
+        }
+        event.withdrawable = 0;
+        event.locked = 0;
+
+        msg!("Released {} lamports of proceeds for {}", amount, event.event_id);
+        Ok(())
+    }
+
+    /// Withdraw `amount` of escrowed proceeds to the organizer before the
+    /// event's general release. Only escrow unlocked by redeemed tickets is
+    /// available until the event date passes, after which the rest is too.
+    /// Callable by the organizer or a co-organizer with `CAN_WITHDRAW`; the
+    /// lamports always go to the organizer.
+    pub fn withdraw_proceeds(ctx: Context<WithdrawProceeds>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let event = &mut ctx.accounts.event;
+        event.require_permission(&ctx.accounts.authority.key(), CoOrganizer::CAN_WITHDRAW)?;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            amount <= event.withdrawable_at(now)?
+                && amount <= escrow_available(&ctx.accounts.escrow)?,
+            TicketError::InsufficientWithdrawable
+        );
+
+        let from_unlocked = amount.min(event.withdrawable);
+        event.withdrawable -= from_unlocked;
+        event.locked = math::sub(event.locked, amount - from_unlocked)?;
+
+        transfer_from_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.organizer.to_account_info(),
+            event,
+            amount,
+        )?;
+
+        msg!("Withdrew {} lamports of proceeds for {}", amount, event.event_id);
+        Ok(())
+    }
+
+    /// Claim a refund for a ticket to a cancelled event.
+    /// Only callable by the ticket's current owner. Returns the escrowed part
+    /// of `price_paid`, less any cancellation fee from the event's refund
+    /// policy, and marks the ticket Cancelled for `reason`, so it can't be
+    /// claimed twice.
+    pub fn claim_refund(ctx: Context<ClaimRefund>, reason: CancelReason) -> Result<()> {
+        require!(
+            ctx.accounts.event.status == EventStatus::Cancelled,
+            TicketError::EventNotCancelled
+        );
+
+        let ticket = &mut ctx.accounts.ticket;
+        require!(
+            ticket.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!ticket.frozen, TicketError::TicketFrozen);
+
+        let mut refund = 0;
+        if ticket.refundable_amount() > 0 {
+            // Lamport refunds only — SPL-paid tickets need a token refund path
+            require!(
+                ticket.payment_mint == Pubkey::default(),
+                TicketError::MintMismatch
+            );
+
+            refund = settle_refund(
+                &ctx.accounts.system_program,
+                &ctx.accounts.escrow,
+                &ctx.accounts.owner.to_account_info(),
+                &ctx.accounts.venue,
+                &mut ctx.accounts.event,
+                ticket.refundable_amount(),
+            )?;
+        }
+
+        ticket.cancel(reason)?;
+        ctx.accounts.event.release_seats(ticket.tier, ticket.admits())?;
+        update_stats(&ctx.accounts.stats, EventStats::record_refund)?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_refund(ticket.price_paid)
+        })?;
+
+        msg!("Refund of {} lamports claimed by {}", refund, ticket.owner);
+        emit!(TicketCancelled {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            refund_amount: refund,
+            reason,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Voluntarily return a ticket before the event's `refund_deadline`.
+    /// Only callable by the ticket's owner. Refunds the escrowed part of
+    /// `price_paid` under the event's refund policy, marks the ticket
+    /// Cancelled and puts its seat back on sale.
+    /// Insured tickets can be returned any time before the event starts and
+    /// get the full `price_paid` back from the insurance pool instead; the
+    /// venue keeps the escrowed proceeds.
+    pub fn request_refund(ctx: Context<RequestRefund>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let event = &ctx.accounts.event;
+        let ticket = &mut ctx.accounts.ticket;
+        let deadline = if ticket.insured {
+            event.event_date
+        } else {
+            event.refund_deadline
+        };
+        require!(now < deadline, TicketError::RefundWindowClosed);
+        require!(
+            ticket.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!ticket.frozen, TicketError::TicketFrozen);
+
+        let mut refund = 0;
+        if ticket.insured {
+            let pool = ctx
+                .accounts
+                .insurance_pool
+                .as_ref()
+                .ok_or(TicketError::InsuranceUnavailable)?;
+            refund = ticket.price_paid;
+            require!(
+                refund <= event.insurance_pool,
+                TicketError::InsufficientInsurancePool
+            );
+            let event_key = event.key();
+            let seeds: &[&[u8]] = &[b"insurance", event_key.as_ref(), &[event.insurance_bump]];
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: pool.to_account_info(),
+                        to: ctx.accounts.owner.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                refund,
+            )?;
+            let event = &mut ctx.accounts.event;
+            event.insurance_pool -= refund;
+            event.unlock(ticket.refundable_amount())?;
+        } else if ticket.refundable_amount() > 0 {
+            // Lamport refunds only — SPL-paid tickets need a token refund path
+            require!(
+                ticket.payment_mint == Pubkey::default(),
+                TicketError::MintMismatch
+            );
+
+            refund = settle_refund(
+                &ctx.accounts.system_program,
+                &ctx.accounts.escrow,
+                &ctx.accounts.owner.to_account_info(),
+                &ctx.accounts.venue,
+                &mut ctx.accounts.event,
+                ticket.refundable_amount(),
+            )?;
+        }
+
+        ticket.cancel(CancelReason::BuyerRequested)?;
+        ctx.accounts.event.release_seats(ticket.tier, ticket.admits())?;
+        update_stats(&ctx.accounts.stats, EventStats::record_refund)?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_refund(ticket.price_paid)
+        })?;
+
+        msg!("Refund of {} lamports requested by {}", refund, ticket.owner);
+        emit!(TicketCancelled {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            refund_amount: refund,
+            reason: CancelReason::BuyerRequested,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Refund `refund_amount` lamports of a ticket out of escrow as a
+    /// goodwill gesture, e.g. for a delayed show. Only callable by the
+    /// organizer. The ticket stays Active; repeated partial refunds add up in
+    /// `refunded_total` and together can't exceed what the venue received.
+    /// Frozen tickets are rejected, as on every other refund path.
+    pub fn partial_refund(ctx: Context<PartialRefund>, refund_amount: u64) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        require!(
+            ticket.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!ticket.frozen, TicketError::TicketFrozen);
+        require!(
+            ticket.payment_mint == Pubkey::default(),
+            TicketError::MintMismatch
+        );
+        require!(
+            refund_amount > 0 && refund_amount <= ticket.refundable_amount(),
+            TicketError::RefundExceedsPaid
+        );
+
+        refund_from_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.owner,
+            &ctx.accounts.event,
+            refund_amount,
+        )?;
+        let event = &mut ctx.accounts.event;
+        event.locked = event.locked.saturating_sub(refund_amount);
+        ticket.refunded_total = math::add(ticket.refunded_total, refund_amount)?;
+
+        msg!(
+            "Partial refund of {} lamports to {} ({} refunded in total)",
+            refund_amount,
+            ticket.owner,
+            ticket.refunded_total
+        );
+        emit!(PartialRefundIssued {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            refund_amount,
+            refunded_total: ticket.refunded_total,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Move a ticket to a pricier tier, e.g. GA to VIP. Only callable by the
+    /// ticket's owner, who pays the difference between the ticket's
+    /// `price_paid` and the target tier's current price, split like a
+    /// purchase (payout split recipients go in `remaining_accounts`). The
+    /// target tier must still have a ticket left; overall capacity is
+    /// unaffected.
+    pub fn upgrade_tier<'info>(
+        ctx: Context<'_, '_, '_, 'info, ChangeTier<'info>>,
+        new_tier: TicketTier,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        let now = Clock::get()?.unix_timestamp;
+        let ticket = &ctx.accounts.ticket;
+        ticket.check_tier_change(new_tier)?;
+        let new_price = ctx.accounts.event.current_price(new_tier, now)?;
+        require!(new_price >= ticket.price_paid, TicketError::InvalidTierChange);
+        let old_tier = ticket.tier;
+        ctx.accounts.event.move_seat(old_tier, new_tier)?;
+
+        let difference = math::sub(new_price, ticket.price_paid)?;
+        ctx.accounts.event.record_revenue(difference)?;
+        let (fee, venue_share) = math::split(difference, ctx.accounts.config.platform_fee_bps)?;
+        let system_program = &ctx.accounts.system_program;
+        let owner = ctx.accounts.owner.to_account_info();
+        if fee > 0 {
+            transfer_lamports(system_program, &owner, &ctx.accounts.fee_destination, fee)?;
+        }
+        pay_venue_share(
+            system_program,
+            &owner,
+            &ctx.accounts.escrow,
+            &mut ctx.accounts.event,
+            ctx.remaining_accounts,
+            venue_share,
+        )?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.tier = new_tier;
+        ticket.price_paid = new_price;
+        ticket.platform_fee = math::add(ticket.platform_fee, fee)?;
+
+        msg!(
+            "Ticket upgraded from {:?} to {:?} for {} lamports",
+            old_tier,
+            new_tier,
+            difference
+        );
+        emit!(TierChanged {
+            ticket: ticket.key(),
+            from: old_tier,
+            to: new_tier,
+            amount: difference,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Move a ticket to a cheaper tier before the event's `refund_deadline`.
+    /// Only callable by the ticket's owner. The difference to the target
+    /// tier's current price is refunded out of escrow under the event's
+    /// refund policy, like `request_refund`; the platform fee isn't.
+    pub fn downgrade_tier(ctx: Context<ChangeTier>, new_tier: TicketTier) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now < ctx.accounts.event.refund_deadline,
+            TicketError::RefundWindowClosed
+        );
+        let ticket = &ctx.accounts.ticket;
+        ticket.check_tier_change(new_tier)?;
+        let new_price = ctx.accounts.event.current_price(new_tier, now)?;
+        require!(new_price <= ticket.price_paid, TicketError::InvalidTierChange);
+        let old_tier = ticket.tier;
+        ctx.accounts.event.move_seat(old_tier, new_tier)?;
+
+        let difference = math::sub(ticket.price_paid, new_price)?.min(ticket.refundable_amount());
+        let refund = settle_refund(
+            &ctx.accounts.system_program,
+            &ctx.accounts.escrow,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.venue,
+            &mut ctx.accounts.event,
+            difference,
+        )?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.tier = new_tier;
+        ticket.price_paid = math::sub(ticket.price_paid, difference)?;
+
+        msg!(
+            "Ticket downgraded from {:?} to {:?}, {} lamports refunded",
+            old_tier,
+            new_tier,
+            refund
+        );
+        emit!(TierChanged {
+            ticket: ticket.key(),
+            from: old_tier,
+            to: new_tier,
+            amount: refund,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Issue a ticket to a recipient who has no wallet yet. Only callable by
+    /// the organizer. The ticket has no owner until someone calls
+    /// `claim_ticket` with the preimage of `claim_hash` (e.g. an emailed claim
+    /// code) before `claim_deadline`. Counts against tier capacity, free.
+    #[allow(clippy::too_many_arguments)]
+    pub fn issue_claimable_ticket(
+        ctx: Context<IssueClaimableTicket>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        claim_hash: [u8; 32],
+        claim_deadline: i64,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        Ticket::validate_fields(&event_id, &event_name, &event.venue, &attendee_name, &seat)?;
+        validate_metadata_uri(&metadata_uri)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            claim_hash != [0u8; 32] && claim_deadline > now,
+            TicketError::InvalidClaimCode
+        );
+        event.reserve_seat(tier)?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event_id = FixedStr::try_from(event_id)?;
+        ticket.event_name = FixedStr::try_from(event_name)?;
+        ticket.event_date = event.event_date;
+        ticket.venue = FixedStr::try_from(event.venue.as_str())?;
+        ticket.attendee_name = FixedStr::try_from(attendee_name)?;
+        ticket.seat = seat.as_deref().map(FixedStr::try_from).transpose()?;
+        ticket.price_paid = 0;
+        ticket.platform_fee = 0;
+        ticket.tier = tier;
+        ticket.transferable = transferable;
+        ticket.metadata_uri = metadata_uri;
+        ticket.claim_hash = claim_hash;
+        ticket.claim_deadline = claim_deadline;
+        ticket.payment_mint = Pubkey::default();
+        ticket.venue_authority = event.venue_authority;
+        ticket.ticket_status = TicketStatus::Active;
+        ticket.cnft_asset_id = Pubkey::default();
+        ticket.owner = Pubkey::default();
+        ticket.created_at = now;
+        ticket.bump = ctx.bumps.ticket;
+        ticket.layout = Ticket::LAYOUT_TAG;
+        ticket.version = Ticket::CURRENT_VERSION;
+        ticket.venue_verified = event.venue_verified;
+        ticket.quantity = 1;
+        return_rent_surplus(&ticket.to_account_info(), &ctx.accounts.organizer.to_account_info())?;
+
+        msg!(
+            "Claimable ticket issued for {} — claim by {}",
+            ticket.attendee_name,
+            claim_deadline
+        );
+        Ok(())
+    }
+
+    /// Hold a ticket for `customer` until `reserved_until`, e.g. for a phone
+    /// or box office sale. Only callable by the organizer, who pays the rent.
+    /// The hold counts against capacity at the price current now; the
+    /// customer pays it with `confirm_reservation`, and after the deadline
+    /// anyone can `expire_reservation`. Reserved tickets can't be redeemed or
+    /// transferred.
+    #[allow(clippy::too_many_arguments)]
+    pub fn reserve_ticket(
+        ctx: Context<ReserveTicket>,
+        event_id: String,
+        event_name: String,
+        attendee_name: String,
+        seat: Option<String>,
+        customer: Pubkey,
+        tier: TicketTier,
+        transferable: bool,
+        metadata_uri: String,
+        reserved_until: i64,
+    ) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        Ticket::validate_fields(&event_id, &event_name, &event.venue, &attendee_name, &seat)?;
+        validate_metadata_uri(&metadata_uri)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(reserved_until > now, TicketError::InvalidReservation);
+        event.check_sales_window(now)?;
+        let price = event.current_price(tier, now)?;
+        event.reserve_seat(tier)?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.event_id = FixedStr::try_from(event_id)?;
+        ticket.event_name = FixedStr::try_from(event_name)?;
+        ticket.event_date = event.event_date;
+        ticket.venue = FixedStr::try_from(event.venue.as_str())?;
+        ticket.attendee_name = FixedStr::try_from(attendee_name)?;
+        ticket.seat = seat.as_deref().map(FixedStr::try_from).transpose()?;
+        ticket.price_paid = price;
+        ticket.platform_fee = 0;
+        ticket.tier = tier;
+        ticket.transferable = transferable;
+        ticket.metadata_uri = metadata_uri;
+        ticket.payment_mint = Pubkey::default();
+        ticket.venue_authority = event.venue_authority;
+        ticket.ticket_status = TicketStatus::Reserved;
+        ticket.cnft_asset_id = Pubkey::default();
+        ticket.owner = customer;
+        ticket.original_buyer = ticket.owner;
+        ticket.created_at = now;
+        ticket.bump = ctx.bumps.ticket;
+        ticket.layout = Ticket::LAYOUT_TAG;
+        ticket.version = Ticket::CURRENT_VERSION;
+        ticket.venue_verified = event.venue_verified;
+        ticket.quantity = 1;
+        return_rent_surplus(&ticket.to_account_info(), &ctx.accounts.organizer.to_account_info())?;
+        ticket.reserved_until = reserved_until;
+
+        msg!(
+            "Ticket for {} held for {} lamports until {}",
+            ticket.attendee_name,
+            price,
+            reserved_until
+        );
+        Ok(())
+    }
+
+    /// Pay for a held ticket and make it Active. Only callable by the
+    /// customer, before `reserved_until`. Payment is split like
+    /// `purchase_ticket`, with payout split recipients passed as writable
+    /// `remaining_accounts`.
+    pub fn confirm_reservation<'info>(
+        ctx: Context<'_, '_, '_, 'info, ConfirmReservation<'info>>,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        let ticket = &ctx.accounts.ticket;
+        require!(
+            ticket.ticket_status == TicketStatus::Reserved,
+            TicketError::NotReserved
+        );
+        require!(
+            Clock::get()?.unix_timestamp <= ticket.reserved_until,
+            TicketError::ReservationExpired
+        );
+        require!(
+            ctx.accounts.event.currency == Currency::Sol,
+            TicketError::CurrencyMismatch
+        );
+
+        let price = ticket.price_paid;
+        ctx.accounts.event.record_revenue(price)?;
+        let (fee, venue_share) = math::split(price, ctx.accounts.config.platform_fee_bps)?;
+        let system_program = &ctx.accounts.system_program;
+        let customer = ctx.accounts.customer.to_account_info();
+        if fee > 0 {
+            transfer_lamports(system_program, &customer, &ctx.accounts.fee_destination, fee)?;
+        }
+        pay_venue_share(
+            system_program,
+            &customer,
+            &ctx.accounts.escrow,
+            &mut ctx.accounts.event,
+            ctx.remaining_accounts,
+            venue_share,
+        )?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.platform_fee = fee;
+        ticket.ticket_status = TicketStatus::Active;
+        ticket.reserved_until = 0;
+        update_stats(&ctx.accounts.stats, |stats| stats.record_sale(price))?;
+        update_profile(&mut ctx.accounts.profile, |profile| {
+            profile.record_sale(price)
+        })?;
+
+        msg!("Reservation for {} paid — Status: Active", ticket.attendee_name);
+        emit!(TicketPurchased {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            event_id: ticket.event_id.to_string(),
+            price_paid: price,
+        });
+        Ok(())
+    }
+
+    /// Release a hold that passed its deadline unpaid. Permissionless; the
+    /// ticket is closed, its rent returned to the organizer, and its
+    /// capacity released for sale.
+    pub fn expire_reservation(ctx: Context<ExpireReservation>) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        require!(
+            ticket.ticket_status == TicketStatus::Reserved,
+            TicketError::NotReserved
+        );
+        require!(
+            Clock::get()?.unix_timestamp > ticket.reserved_until,
+            TicketError::ReservationNotExpired
+        );
+
+        ctx.accounts.event.release_seat(ticket.tier)?;
+
+        msg!("Hold for {} expired", ticket.attendee_name);
+        Ok(())
+    }
+
+    /// Claim an issued ticket by revealing the preimage of its `claim_hash`.
+    /// The signer becomes the owner. The code is visible on-chain once used,
+    /// so each code works exactly once.
+    pub fn claim_ticket(ctx: Context<ClaimTicket>, claim_code: Vec<u8>) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.check_claim(Clock::get()?.unix_timestamp)?;
+        require!(
+            hash(&claim_code).to_bytes() == ticket.claim_hash,
+            TicketError::InvalidClaimCode
+        );
+
+        ticket.owner = ctx.accounts.claimant.key();
+        ticket.original_buyer = ticket.owner;
+        ticket.claim_hash = [0u8; 32];
+
+        msg!("Ticket claimed by {}", ticket.owner);
+        emit!(TicketPurchased {
+            ticket: ticket.key(),
+            owner: ticket.owner,
+            event_id: ticket.event_id.to_string(),
+            price_paid: 0,
+        });
+        Ok(())
+    }
+
+    /// Reclaim an issued ticket nobody claimed before its deadline.
+    /// Only callable by the organizer. The ticket is closed, its rent returned
+    /// to the organizer, and its capacity released for sale.
+    pub fn reclaim_ticket(ctx: Context<ReclaimTicket>) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        ticket.check_reclaim(Clock::get()?.unix_timestamp)?;
+
+        ctx.accounts.event.release_seat(ticket.tier)?;
+
+        msg!("Unclaimed ticket for {} reclaimed", ticket.attendee_name);
+        Ok(())
+    }
+
+    /// Fold `source` into `target`, two Active general admission tickets the
+    /// signer owns for the same event, e.g. to gift a block of seats as one.
+    /// `target` then admits both tickets' `quantity` and carries their
+    /// combined `price_paid`, so a later refund covers both; `source` is
+    /// closed and its rent returned to the owner. Tickets with a seat, a
+    /// cNFT, insurance or a token payment can't be merged, nor can tickets
+    /// to a cancelled or ended event.
+    pub fn merge_tickets(ctx: Context<MergeTickets>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            event.status != EventStatus::Ended,
+            TicketError::InvalidEventStatus
+        );
+        let source = &ctx.accounts.source;
+        let target = &mut ctx.accounts.target;
+        for ticket in [&**source, &**target] {
+            require!(
+                ticket.owner == ctx.accounts.owner.key(),
+                TicketError::OwnerMismatch
+            );
+            require!(
+                ticket.ticket_status == TicketStatus::Active,
+                TicketError::TicketNotActive
+            );
+            require!(!ticket.frozen, TicketError::TicketFrozen);
+            require!(
+                ticket.tier == TicketTier::GeneralAdmission
+                    && ticket.seat.is_none()
+                    && ticket.cnft_asset_id == Pubkey::default()
+                    && !ticket.insured
+                    && ticket.payment_mint == Pubkey::default(),
+                TicketError::TicketsNotMergeable
+            );
+        }
+        require!(
+            source.event_id == target.event_id && source.transferable == target.transferable,
+            TicketError::TicketsNotMergeable
+        );
+
+        target.quantity = math::add(target.admits() as u64, source.admits() as u64)?
+            .try_into()
+            .map_err(|_| TicketError::ArithmeticOverflow)?;
+        target.price_paid = math::add(target.price_paid, source.price_paid)?;
+        target.platform_fee = math::add(target.platform_fee, source.platform_fee)?;
+        target.refunded_total = math::add(target.refunded_total, source.refunded_total)?;
+
+        msg!(
+            "Merged ticket into {} for {}, now admitting {}",
+            target.key(),
+            target.attendee_name,
+            target.quantity
+        );
+        Ok(())
+    }
+
+    /// Break `recipients.len()` admissions (at most `MAX_BATCH_SIZE`) off a
+    /// group ticket into single tickets, e.g. for a group leader handing
+    /// out a bulk purchase. Each new ticket PDA, followed by its recipient's
+    /// `BlacklistEntry` PDA (which must not exist), is passed in order as
+    /// `remaining_accounts`; the tickets are created here, paid for by the
+    /// owner. They keep the source's event, tier, transfer history and
+    /// per-person share of `price_paid`, and the source keeps at least one
+    /// admission. Seats are already counted, so capacity is unchanged.
+    /// Giving a ticket to someone else is a transfer: royalty enforcement
+    /// and every `record_transfer` rule apply.
+    pub fn split_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, SplitTicket<'info>>,
+        recipients: Vec<SplitRecipient>,
+    ) -> Result<()> {
+        check_remaining_accounts(ctx.remaining_accounts)?;
+        require!(
+            !recipients.is_empty() && recipients.len() <= MAX_BATCH_SIZE,
+            TicketError::BatchTooLarge
+        );
+        require!(
+            ctx.remaining_accounts.len() == 2 * recipients.len(),
+            TicketError::TicketAccountMismatch
+        );
+
+        let source = &mut ctx.accounts.source;
+        require!(
+            source.owner == ctx.accounts.owner.key(),
+            TicketError::OwnerMismatch
+        );
+        require!(
+            source.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!source.frozen, TicketError::TicketFrozen);
+        require!(
+            source.admits() as usize > recipients.len(),
+            TicketError::InsufficientQuantity
+        );
+        for recipient in &recipients {
+            require!(
+                recipient.owner == source.owner
+                    || (source.transferable
+                        && ctx.accounts.event.allows_free_transfer_to(&recipient.owner)),
+                TicketError::TransferRequiresSale
+            );
+        }
+
+        let quantity = source.admits() as u64;
+        let count = recipients.len() as u64;
+        let unit_price = source.price_paid / quantity;
+        let unit_fee = source.platform_fee / quantity;
+        let unit_refunded = source.refunded_total / quantity;
+        source.quantity = (quantity - count) as u32;
+        source.price_paid = math::sub(source.price_paid, math::mul(unit_price, count)?)?;
+        source.platform_fee = math::sub(source.platform_fee, math::mul(unit_fee, count)?)?;
+        source.refunded_total =
+            math::sub(source.refunded_total, math::mul(unit_refunded, count)?)?;
+
+        let template = Ticket::clone(source);
+        let event_id = template.event_id.to_string();
+        let now = Clock::get()?.unix_timestamp;
+        let system_program = &ctx.accounts.system_program;
+        let payer = ctx.accounts.owner.to_account_info();
+        for (accounts, recipient) in ctx.remaining_accounts.chunks(2).zip(recipients) {
+            let (info, blacklist) = (&accounts[0], &accounts[1]);
+            let (expected, bump) =
+                ticket_pda(&event_id, &recipient.attendee_name, None, &recipient.owner);
+            require_keys_eq!(info.key(), expected, TicketError::TicketAccountMismatch);
+            require_keys_eq!(
+                blacklist.key(),
+                blacklist_pda(&event_id, &recipient.owner).0,
+                TicketError::TicketAccountMismatch
+            );
+            require!(blacklist.data_is_empty(), TicketError::AttendeeBlacklisted);
+
+            let mut ticket = Ticket {
+                attendee_name: FixedStr::try_from(recipient.attendee_name)?,
+                seat: None,
+                price_paid: unit_price,
+                platform_fee: unit_fee,
+                refunded_total: unit_refunded,
+                created_at: now,
+                bump,
+                delegate: None,
+                delegate_expiry: 0,
+                quantity: 1,
+                ..template.clone()
+            };
+            if recipient.owner != template.owner {
+                ticket.record_transfer(recipient.owner, &ctx.accounts.event, now)?;
+            }
+            init_ticket_account(system_program, &payer, info, &ticket)?;
+        }
+
+        msg!(
+            "Split {} tickets off {}, which now admits {}",
+            count,
+            source.key(),
+            source.quantity
+        );
+        Ok(())
+    }
+
+    /// Close a ticket PDA and return its rent to the owner.
+    /// Allowed once the ticket is Redeemed, Cancelled or NoShow (so an
+    /// expired ticket can be closed straight after `mark_no_show`), or once
+    /// the event is more than `CLOSE_GRACE_PERIOD` in the past. Holds are
+    /// never closed here: `expire_reservation` puts their seat back on sale.
+    pub fn close_ticket(ctx: Context<CloseTicket>) -> Result<()> {
+        let ticket = &ctx.accounts.ticket;
+        let now = Clock::get()?.unix_timestamp;
+
+        let settled = matches!(
+            ticket.ticket_status,
+            TicketStatus::Redeemed | TicketStatus::Cancelled | TicketStatus::NoShow
+        );
+        let expired = ticket.ticket_status == TicketStatus::Active
+            && now > ticket.event_date.saturating_add(CLOSE_GRACE_PERIOD);
+        require!(settled || expired, TicketError::CannotCloseActiveTicket);
+
+        msg!(
+            "Ticket closed for {} — rent returned to {}",
+            ticket.attendee_name,
+            ticket.owner
+        );
+        Ok(())
+    }
+
+    /// Flag an unredeemed ticket as NoShow once the event's redemption
+    /// window has closed, counting it in `no_show_count`. Permissionless,
+    /// since the outcome is fixed by then; marking a NoShow ticket again is
+    /// a no-op.
+    pub fn mark_no_show(ctx: Context<MarkNoShow>) -> Result<()> {
+        let ticket = &mut ctx.accounts.ticket;
+        let event = &mut ctx.accounts.event;
+        match ticket.ticket_status {
+            TicketStatus::NoShow => {
+                msg!("Ticket already marked as a no-show");
+                return Ok(());
+            }
+            TicketStatus::Active => {}
+            _ => return err!(TicketError::TicketNotActive),
+        }
+        require!(
+            event.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            Clock::get()?.unix_timestamp > event.redemption_end(),
+            TicketError::RedemptionWindowOpen
+        );
+
+        ticket.ticket_status = TicketStatus::NoShow;
+        event.no_show_count = event
+            .no_show_count
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+
+        msg!(
+            "Ticket for {} marked as a no-show ({} so far)",
+            ticket.attendee_name,
+            event.no_show_count
+        );
+        Ok(())
+    }
+
+    /// Upgrade a ticket created with an older, shorter layout to the
+    /// current `Ticket::SIZE` and `Ticket::CURRENT_VERSION`. Fields missing
+    /// from the old layout read as zero: an empty history and no transfers.
+    /// Only the ticket owner or the event organizer may migrate, and the
+    /// signer pays any extra rent. A no-op for tickets already current.
+    pub fn migrate_ticket(ctx: Context<MigrateTicket>) -> Result<()> {
+        let account = &ctx.accounts.ticket;
+        let current_len = account.data_len();
+        let mut ticket = Ticket::decode_any_version(&account.try_borrow_data()?)?;
+
+        let event = &ctx.accounts.event;
+        let event_pda = Pubkey::create_program_address(
+            &[b"event", seed_hash(&ticket.event_id).as_ref(), &[event.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| TicketError::InvalidTicketAccount)?;
+        require_keys_eq!(event.key(), event_pda, TicketError::InvalidTicketAccount);
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == ticket.owner || authority == event.organizer,
+            TicketError::UnauthorizedMigration
+        );
+
+        if ticket.version >= Ticket::CURRENT_VERSION && current_len >= Ticket::SIZE {
+            msg!("Ticket already at version {}", ticket.version);
+            return Ok(());
+        }
+        let from_version = ticket.version.max(1);
+
+        if current_len < Ticket::SIZE {
+            let required = Rent::get()?.minimum_balance(Ticket::SIZE);
+            let top_up = required.saturating_sub(account.lamports());
+            if top_up > 0 {
+                transfer_lamports(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.authority.to_account_info(),
+                    &account.to_account_info(),
+                    top_up,
+                )?;
+            }
+            account.realloc(Ticket::SIZE, true)?;
+        }
+
+        ticket.layout = Ticket::LAYOUT_TAG;
+        ticket.version = Ticket::CURRENT_VERSION;
+        let mut data = account.try_borrow_mut_data()?;
+        data.fill(0);
+        ticket.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "Ticket migrated from v{} ({} bytes) to v{} ({} bytes)",
+            from_version,
+            current_len,
+            Ticket::CURRENT_VERSION,
+            Ticket::SIZE
+        );
+        Ok(())
+    }
+
+    /// Get ticket information (view-only helper).
+    /// Returns a `TicketView` readable from a simulated transaction; the log
+    /// lines are kept for on-chain debugging. The status is the effective
+    /// one, so an unused ticket past its event's redemption window (and
+    /// late-entry grace) shows as NoShow even before `mark_no_show` runs.
+    pub fn get_ticket_info(ctx: Context<GetTicketInfo>) -> Result<TicketView> {
+        let ticket = &ctx.accounts.ticket;
+        let status = ticket.effective_status(&ctx.accounts.event, Clock::get()?.unix_timestamp);
+
+        msg!("=== Ticket Info ===");
+        msg!("Event: {}", ticket.event_name);
+        msg!("Venue: {}", ticket.venue);
+        msg!("Attendee: {}", ticket.attendee_name);
+        msg!("Date: {}", ticket.event_date);
+        msg!("UTC offset: {} minutes", ctx.accounts.event.tz_offset_minutes);
+        msg!("Price: {} lamports", ticket.price_paid);
+        msg!("Status: {:?}", status);
+        msg!("Owner: {}", ticket.owner);
+        msg!("cNFT: {}", ticket.cnft_asset_id);
+
+        Ok(TicketView {
+            status,
+            tz_offset_minutes: ctx.accounts.event.tz_offset_minutes,
+            ..TicketView::from(&**ticket)
+        })
+    }
+
+    /// Whether the ticket would be admitted if scanned now (view-only).
+    /// Runs the same checks as redemption without changing anything and
+    /// returns a `TicketValidity`, whose `reason` says why not.
+    pub fn verify_ticket(ctx: Context<GetTicketInfo>) -> Result<TicketValidity> {
+        let now = Clock::get()?.unix_timestamp;
+        let validity = TicketValidity::check(&ctx.accounts.ticket, &ctx.accounts.event, now);
+
+        msg!("Valid: {} (reason {})", validity.valid, validity.reason);
+        Ok(validity)
+    }
+
+    /// Rate an event 1–5 as someone who attended it. Only callable by the
+    /// owner of a Redeemed ticket, once per ticket. Scores are summed on the
+    /// event, so its average is `rating_sum / rating_count`.
+    pub fn submit_rating(ctx: Context<SubmitRating>, score: u8) -> Result<()> {
+        require!(
+            ctx.accounts.ticket.ticket_status == TicketStatus::Redeemed,
+            TicketError::DidNotAttend
+        );
+        require!(
+            (Rating::MIN_SCORE..=Rating::MAX_SCORE).contains(&score),
+            TicketError::InvalidRating
+        );
+
+        let rating = &mut ctx.accounts.rating;
+        rating.ticket = ctx.accounts.ticket.key();
+        rating.rater = ctx.accounts.owner.key();
+        rating.score = score;
+        rating.rated_at = Clock::get()?.unix_timestamp;
+        rating.bump = ctx.bumps.rating;
+
+        let event = &mut ctx.accounts.event;
+        event.rating_sum = math::add(event.rating_sum, score as u64)?;
+        event.rating_count = event
+            .rating_count
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+
+        msg!("{} rated {} out of {}", event.event_id, score, Rating::MAX_SCORE);
+        Ok(())
+    }
+
+    /// Get attendance counters for an event (view-only helper), so a
+    /// dashboard can compute attendance without scanning every ticket.
+    pub fn get_event_stats(ctx: Context<GetAttendanceView>) -> Result<AttendanceView> {
+        let event = &ctx.accounts.event;
+        msg!(
+            "Sold: {} — Redeemed: {} — No-shows: {}",
+            event.tickets_sold,
+            event.redeemed_count,
+            event.no_show_count
+        );
+        Ok(AttendanceView::from(&**event))
+    }
+}
+
+// --- Helpers ---
+
+/// Burn the seller's ticket cNFT and mint its replacement to the buyer, for
+/// `buy_listing`. Skipped, leaving the cNFT alone, when the accounts aren't
+/// passed; once they are, any mismatch fails the purchase.
+fn remint_cnft<'info>(
+    accounts: &mut BuyListing<'info>,
+    authority_bump: Option<u8>,
+    leaf: &CnftLeaf,
+    proof: &[AccountInfo<'info>],
+) -> Result<()> {
+    let (
+        Some(cnft_authority),
+        Some(tree_config),
+        Some(merkle_tree),
+        Some(log_wrapper),
+        Some(compression_program),
+        Some(bubblegum_program),
+        Some(authority_bump),
+    ) = (
+        &accounts.cnft_authority,
+        &accounts.tree_config,
+        &accounts.merkle_tree,
+        &accounts.log_wrapper,
+        &accounts.compression_program,
+        &accounts.bubblegum_program,
+        authority_bump,
+    )
+    else {
+        msg!("cNFT left with the seller — cNFT accounts not passed");
+        return Ok(());
+    };
+
+    require_keys_eq!(
+        bubblegum::asset_id(&merkle_tree.key(), leaf.nonce),
+        accounts.ticket.cnft_asset_id,
+        TicketError::CnftMismatch
+    );
+    let (num_minted, capacity) =
+        bubblegum::tree_usage(tree_config).ok_or(TicketError::CnftMismatch)?;
+    require!(num_minted < capacity, TicketError::CnftTreeFull);
+
+    let seeds: &[&[u8]] = &[b"cnft_authority", &[authority_bump]];
+    let seller = accounts.seller.to_account_info();
+    bubblegum::burn_as_delegate(
+        bubblegum::BurnAccounts {
+            bubblegum_program,
+            tree_config,
+            leaf_owner: &seller,
+            leaf_delegate: cnft_authority,
+            merkle_tree,
+            log_wrapper,
+            compression_program,
+            system_program: &accounts.system_program,
+            proof,
+        },
+        leaf,
+        &[seeds],
+    )?;
+
+    let buyer = accounts.buyer.to_account_info();
+    let name: String = accounts.ticket.event_name.chars().take(32).collect();
+    bubblegum::mint(
+        bubblegum::MintAccounts {
+            bubblegum_program,
+            tree_config,
+            leaf_owner: &buyer,
+            leaf_delegate: cnft_authority,
+            merkle_tree,
+            payer: &buyer,
+            tree_delegate: cnft_authority,
+            log_wrapper,
+            compression_program,
+            system_program: &accounts.system_program,
+        },
+        &name,
+        "TICKET",
+        &accounts.ticket.metadata_uri,
+        &[seeds],
+    )?;
+
+    let asset_id = bubblegum::asset_id(&merkle_tree.key(), num_minted);
+    msg!(
+        "cNFT {} burned, {} minted to {}",
+        accounts.ticket.cnft_asset_id,
+        asset_id,
+        accounts.ticket.owner
+    );
+    accounts.ticket.cnft_asset_id = asset_id;
+    Ok(())
+}
+
+/// Transfer fee a Token-2022 mint withholds on a transfer of `amount` in the
+/// current epoch. Zero for legacy mints and mints without the extension.
+fn transfer_fee_of(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| TicketError::ArithmeticOverflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Arguments shared by every SOL purchase path.
+struct PurchaseParams {
+    event_id: String,
+    event_name: String,
+    attendee_name: String,
+    seat: Option<String>,
+    price_lamports: u64,
+    cnft_asset_id: Pubkey,
+    tier: TicketTier,
+    transferable: bool,
+    metadata_uri: String,
+    /// Price was derived on-chain (oracle, promo code), so skip the tier
+    /// price check.
+    priced_on_chain: bool,
+    /// Buyer pays the event's insurance premium for refund protection.
+    insured: bool,
+    /// Wallet credited with a referral reward out of the platform fee.
+    referrer: Option<Pubkey>,
+    /// Hash of the terms the buyer accepted; must match the event's.
+    accepted_terms: [u8; 32],
+}
+
+/// Common body of the SOL purchase instructions: validate, count the sale,
+/// take payment and initialize the ticket PDA.
+fn process_purchase<'info>(
+    ctx: Context<'_, '_, '_, 'info, PurchaseTicket<'info>>,
+    params: PurchaseParams,
+) -> Result<()> {
+    check_remaining_accounts(ctx.remaining_accounts)?;
+    let PurchaseParams {
+        event_id,
+        event_name,
+        attendee_name,
+        seat,
+        price_lamports,
+        cnft_asset_id,
+        tier,
+        transferable,
+        metadata_uri,
+        priced_on_chain,
+        insured,
+        referrer,
+        accepted_terms,
+    } = params;
+
+    // Dated and placed by the event, or by the tour stop on a tour
+    let venue = match &ctx.accounts.stop {
+        Some(stop) => stop.venue.clone(),
+        None => ctx.accounts.event.venue.clone(),
+    };
+    Ticket::validate_fields(&event_id, &event_name, &venue, &attendee_name, &seat)?;
+    validate_metadata_uri(&metadata_uri)?;
+    require!(
+        ctx.accounts.event.currency == Currency::Sol,
+        TicketError::CurrencyMismatch
+    );
+    ctx.accounts.event.check_attestation(
+        ctx.accounts.attestation.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+    ctx.accounts.event.check_terms(&accepted_terms)?;
+    let platform_fee_bps = ctx.accounts.config.platform_fee_bps;
+    ctx.accounts
+        .event
+        .check_sales_window(Clock::get()?.unix_timestamp)?;
+    if ctx.accounts.event.venue_stops > 0 {
+        ctx.accounts
+            .stop
+            .as_mut()
+            .ok_or(TicketError::VenueStopRequired)?
+            .reserve_seat()?;
+    }
+    let mut price_lamports = price_lamports;
+    if priced_on_chain {
+        ctx.accounts.event.reserve_seat(tier)?;
+    } else {
+        let now = Clock::get()?.unix_timestamp;
+        price_lamports = ctx.accounts.event.clearing_price(tier, price_lamports, now)?;
+        ctx.accounts.event.record_sale(tier, price_lamports, now)?;
+    }
+    ctx.accounts.event.record_revenue(price_lamports)?;
+    ctx.accounts
+        .allocation
+        .record_purchase(ctx.accounts.event.max_per_buyer, ctx.bumps.allocation)?;
+
+    if let Some(referrer) = referrer {
+        require_keys_neq!(referrer, ctx.accounts.buyer.key(), TicketError::SelfReferral);
+        let rewards = ctx
+            .accounts
+            .referral_rewards
+            .as_ref()
+            .ok_or(TicketError::ReferralAccountMismatch)?;
+        require_keys_eq!(rewards.referrer, referrer, TicketError::ReferralAccountMismatch);
+    }
+
+    // Transfer SOL if not a free event, splitting off the platform fee
+    let mut platform_fee = 0;
+    if price_lamports > 0 {
+        // A venue paying itself would hide accounting bugs behind a no-op transfer
+        require_keys_neq!(
+            ctx.accounts.buyer.key(),
+            ctx.accounts.event.venue_authority,
+            TicketError::BuyerIsVenue
+        );
+        require!(
+            ctx.accounts.buyer.lamports() >= price_lamports,
+            TicketError::InsufficientFunds
+        );
+        let (fee, venue_share) = math::split(price_lamports, platform_fee_bps)?;
+
+        let system_program = &ctx.accounts.system_program;
+        let buyer = ctx.accounts.buyer.to_account_info();
+        let mut referral_reward = 0;
+        if let Some(rewards) = ctx.accounts.referral_rewards.as_mut().filter(|_| referrer.is_some()) {
+            referral_reward = ctx
+                .accounts
+                .event
+                .take_referral_reward(price_lamports, fee)?;
+            if referral_reward > 0 {
+                transfer_lamports(system_program, &buyer, &rewards.to_account_info(), referral_reward)?;
+                rewards.earned = math::add(rewards.earned, referral_reward)?;
+                msg!("Referral reward of {} lamports to {}", referral_reward, rewards.referrer);
+            }
+        }
+        let treasury_fee = math::sub(fee, referral_reward)?;
+        if treasury_fee > 0 {
+            transfer_lamports(system_program, &buyer, &ctx.accounts.fee_destination, treasury_fee)?;
+        }
+        pay_venue_share(
+            system_program,
+            &buyer,
+            &ctx.accounts.escrow,
+            &mut ctx.accounts.event,
+            ctx.remaining_accounts,
+            venue_share,
+        )?;
+        platform_fee = fee;
+        msg!(
+            "Payment of {} lamports: {} to the venue, {} platform fee",
+            price_lamports,
+            venue_share,
+            fee
+        );
+    } else {
+        msg!("Free event — no payment required");
+    }
+
+    if insured {
+        let premium = ctx.accounts.event.insurance_premium;
+        let pool = ctx
+            .accounts
+            .insurance_pool
+            .as_ref()
+            .ok_or(TicketError::InsuranceUnavailable)?;
+        require!(premium > 0, TicketError::InsuranceUnavailable);
+        transfer_lamports(
+            &ctx.accounts.system_program,
+            &ctx.accounts.buyer.to_account_info(),
+            &pool.to_account_info(),
+            premium,
+        )?;
+        let event = &mut ctx.accounts.event;
+        event.insurance_pool = math::add(event.insurance_pool, premium)?;
+        msg!("Insurance premium of {} lamports paid", premium);
+    }
+
+    // Initialize the ticket PDA
+    let ticket = &mut ctx.accounts.ticket;
+    ticket.event_id = FixedStr::try_from(event_id)?;
+    ticket.event_name = FixedStr::try_from(event_name)?;
+    ticket.event_date = ctx.accounts.event.event_date;
+    ticket.venue = FixedStr::try_from(venue)?;
+    ticket.attendee_name = FixedStr::try_from(attendee_name)?;
+    ticket.seat = seat.as_deref().map(FixedStr::try_from).transpose()?;
+    ticket.price_paid = price_lamports;
+    ticket.platform_fee = platform_fee;
+    ticket.tier = tier;
+    ticket.transferable = transferable;
+    ticket.metadata_uri = metadata_uri;
+    ticket.payment_mint = Pubkey::default();
+    ticket.venue_authority = ctx.accounts.event.venue_authority;
+    ticket.ticket_status = TicketStatus::Active;
+    ticket.cnft_asset_id = cnft_asset_id;
+    ticket.owner = ctx.accounts.buyer.key();
+    ticket.original_buyer = ticket.owner;
+    ticket.created_at = Clock::get()?.unix_timestamp;
+    ticket.bump = ctx.bumps.ticket;
+    ticket.layout = Ticket::LAYOUT_TAG;
+    ticket.version = Ticket::CURRENT_VERSION;
+    ticket.venue_verified = ctx.accounts.event.venue_verified;
+    ticket.quantity = 1;
+    return_rent_surplus(&ticket.to_account_info(), &ctx.accounts.buyer.to_account_info())?;
+    ticket.accepted_terms = accepted_terms;
+    ticket.insured = insured;
+    update_stats(&ctx.accounts.stats, |stats| stats.record_sale(price_lamports))?;
+    update_profile(&mut ctx.accounts.profile, |profile| {
+        profile.record_sale(price_lamports)
+    })?;
+
+    msg!("Ticket created for {} — Status: Active", ticket.attendee_name);
+    emit!(TicketPurchased {
+        ticket: ticket.key(),
+        owner: ticket.owner,
+        event_id: ticket.event_id.to_string(),
+        price_paid: ticket.price_paid,
+    });
+    Ok(())
+}
+
+/// Apply `update` to the event's zero-copy stats, if the caller passed them.
+fn update_stats(
+    stats: &Option<AccountLoader<EventStats>>,
+    update: impl FnOnce(&mut EventStats) -> Result<()>,
+) -> Result<()> {
+    match stats {
+        Some(stats) => update(&mut *stats.load_mut()?),
+        None => Ok(()),
+    }
+}
+
+/// Apply `update` to the organizer's profile, if the caller passed it.
+fn update_profile(
+    profile: &mut Option<Account<OrganizerProfile>>,
+    update: impl FnOnce(&mut OrganizerProfile) -> Result<()>,
+) -> Result<()> {
+    match profile {
+        Some(profile) => update(profile),
+        None => Ok(()),
+    }
+}
+
+/// Create and write a ticket PDA that wasn't declared in the Accounts struct
+/// (e.g. passed via `remaining_accounts`). Fails if the account already exists.
+fn init_ticket_account<'info>(
+    system_program: &Program<'info, System>,
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    ticket: &Ticket,
+) -> Result<()> {
+    require!(
+        account.lamports() == 0 && account.data_is_empty(),
+        TicketError::TicketAlreadyExists
+    );
+
+    let holder = ticket_holder_seed(&ticket.attendee_name, ticket.seat.as_deref(), &ticket.owner);
+    let event_hash = seed_hash(&ticket.event_id);
+    let signer_seeds: &[&[u8]] = &[b"ticket", &event_hash, &holder, &[ticket.bump]];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        Rent::get()?.minimum_balance(Ticket::SIZE),
+        Ticket::SIZE as u64,
+        &crate::ID,
+    )?;
+
+    let mut data = account.try_borrow_mut_data()?;
+    ticket.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Shared redemption state change for `redeem_ticket` and `redeem_and_burn`.
+/// A Redeemed ticket may be scanned again only if the event allows re-entry.
+/// A first scan unlocks the ticket's escrowed price for `withdraw_proceeds`.
+fn redeem(ticket: &mut Account<Ticket>, event: &mut Event, day_index: u8) -> Result<()> {
+    require!(!ticket.frozen, TicketError::TicketFrozen);
+    require!(day_index < event.days.max(1), TicketError::InvalidDayIndex);
+    let multi_day = event.days > 1;
+    require!(
+        !(multi_day && ticket.is_checked_in(event, day_index)),
+        TicketError::AlreadyCheckedInForDay
+    );
+    match ticket.ticket_status {
+        TicketStatus::Active => {
+            event.unlock(ticket.refundable_amount())?;
+            event.redeemed_count = event
+                .redeemed_count
+                .checked_add(1)
+                .ok_or(TicketError::ArithmeticOverflow)?;
+        }
+        TicketStatus::Redeemed => require!(
+            multi_day || event.reentry_allowed,
+            TicketError::ReentryNotAllowed
+        ),
+        _ => return err!(TicketError::TicketNotActive),
+    }
+    require!(
+        event.status != EventStatus::Cancelled,
+        TicketError::EventCancelled
+    );
+    require!(
+        event.status == EventStatus::Live,
+        TicketError::InvalidEventStatus
+    );
+    let now = Clock::get()?.unix_timestamp;
+    event.check_redemption_window(now)?;
+
+    ticket.ticket_status = TicketStatus::Redeemed;
+    if multi_day {
+        ticket.day_checked_in |= 1 << day_index;
+    }
+    ticket.scan_count = ticket
+        .scan_count
+        .checked_add(1)
+        .ok_or(TicketError::ArithmeticOverflow)?;
+    ticket.last_scanned_at = now;
+
+    msg!(
+        "Ticket redeemed for {} at {} (scan {})",
+        ticket.attendee_name,
+        ticket.venue,
+        ticket.scan_count
+    );
+    emit!(TicketRedeemed {
+        ticket: ticket.key(),
+        venue: ticket.venue.to_string(),
+        timestamp: now,
+        scan_count: ticket.scan_count,
+    });
+    Ok(())
+}
+
+/// Move lamports between accounts via the system program.
+/// `from` must be a signer of the outer instruction.
+fn transfer_lamports<'info>(
+    system_program: &Program<'info, System>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: from.clone(),
+            to: to.clone(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Lamports an escrow PDA holds above its rent-exempt minimum.
+fn escrow_available(escrow: &SystemAccount) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    Ok(escrow.lamports().saturating_sub(rent_exempt))
+}
+
+/// Pay the venue's share of a SOL sale: into the event escrow (as locked
+/// funds), or, when the event has payout splits, straight to each recipient. `recipients` must
+/// list the split accounts in order; the last one absorbs rounding dust.
+fn pay_venue_share<'info>(
+    system_program: &Program<'info, System>,
+    buyer: &AccountInfo<'info>,
+    escrow: &SystemAccount<'info>,
+    event: &mut Event,
+    recipients: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    if event.payout_splits.is_empty() {
+        transfer_lamports(system_program, buyer, &escrow.to_account_info(), amount)?;
+        event.locked = math::add(event.locked, amount)?;
+        return Ok(());
+    }
+
+    require!(
+        recipients.len() == event.payout_splits.len(),
+        TicketError::SplitRecipientMismatch
+    );
+    let mut remaining = amount;
+    for (i, (split, recipient)) in event.payout_splits.iter().zip(recipients).enumerate() {
+        require_keys_eq!(
+            recipient.key(),
+            split.recipient,
+            TicketError::SplitRecipientMismatch
+        );
+        let share = if i + 1 == recipients.len() {
+            remaining
+        } else {
+            math::fee_of(amount, split.bps)?
+        };
+        remaining = math::sub(remaining, share)?;
+        if share > 0 {
+            transfer_lamports(system_program, buyer, recipient, share)?;
+        }
+    }
+    Ok(())
+}
+
+/// The optional `original_buyer` account, required once a resale owes it a
+/// royalty.
+fn original_buyer_account<'info>(
+    original_buyer: &Option<UncheckedAccount<'info>>,
+) -> Result<AccountInfo<'info>> {
+    original_buyer
+        .as_ref()
+        .map(|account| account.to_account_info())
+        .ok_or_else(|| error!(TicketError::OriginalBuyerMismatch))
+}
+
+/// Send anything a freshly initialized `account` holds above rent exemption
+/// for its data back to `payer`, e.g. lamports left at the address before
+/// init. Fails with `NotRentExempt` if it holds less.
+fn return_rent_surplus(account: &AccountInfo, payer: &AccountInfo) -> Result<()> {
+    return_surplus_above(&Rent::get()?, account, payer)
+}
+
+/// `return_rent_surplus` against a given `rent`.
+fn return_surplus_above(rent: &Rent, account: &AccountInfo, payer: &AccountInfo) -> Result<()> {
+    let required = rent.minimum_balance(account.data_len());
+    let balance = account.lamports();
+    require!(balance >= required, TicketError::NotRentExempt);
+    move_lamports(account, payer, balance - required)
+}
+
+/// Move lamports out of an account this program owns (e.g. an Offer PDA)
+/// by adjusting balances directly; no CPI is needed.
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let remaining = math::sub(from.lamports(), amount)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = math::add(to.lamports(), amount)?;
+    Ok(())
+}
+
+/// Move lamports out of an event's escrow PDA, signing with its seeds.
+fn transfer_from_escrow<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    event: &Account<'info, Event>,
+    amount: u64,
+) -> Result<()> {
+    let event_key = event.key();
+    let seeds: &[&[u8]] = &[b"escrow", event_key.as_ref(), &[event.escrow_bump]];
+    let signer_seeds = &[seeds];
+    let cpi_context = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: escrow.to_account_info(),
+            to: to.clone(),
+        },
+        signer_seeds,
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Refund a ticket holder out of escrow, failing cleanly if the escrow
+/// can't cover it without dropping below rent-exemption.
+fn refund_from_escrow<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    to: &AccountInfo<'info>,
+    event: &Account<'info, Event>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        amount <= escrow_available(escrow)?,
+        TicketError::InsufficientEscrow
+    );
+    transfer_from_escrow(system_program, escrow, to, event, amount)
+}
+
+/// Pay out a refund of `refundable` lamports under the event's refund policy:
+/// the owner gets the applicable share, the venue the retained part.
+/// Returns the amount refunded to the owner.
+fn settle_refund<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    owner: &AccountInfo<'info>,
+    venue: &AccountInfo<'info>,
+    event: &mut Account<'info, Event>,
+    refundable: u64,
+) -> Result<u64> {
+    if refundable == 0 {
+        return Ok(0);
+    }
+    event.locked = event.locked.saturating_sub(refundable);
+    let refund_bps = event.refund_bps(Clock::get()?.unix_timestamp);
+    let refund = math::fee_of(refundable, refund_bps)?;
+    let retained = math::sub(refundable, refund)?;
+
+    if refund > 0 {
+        refund_from_escrow(system_program, escrow, owner, event, refund)?;
+    }
+    if retained > 0 {
+        refund_from_escrow(system_program, escrow, venue, event, retained)?;
+        msg!("Cancellation fee of {} lamports kept by the venue", retained);
+    }
+    Ok(refund)
+}
+
+/// Splits must be empty, or at most `Event::MAX_PAYOUT_SPLITS` entries whose
+/// bps add up to exactly `MAX_BPS`.
+fn validate_payout_splits(splits: &[PayoutSplit]) -> Result<()> {
+    if splits.is_empty() {
+        return Ok(());
+    }
+    require!(
+        splits.len() <= Event::MAX_PAYOUT_SPLITS,
+        TicketError::TooManySplits
+    );
+    let total: u32 = splits.iter().map(|split| split.bps as u32).sum();
+    require!(total == MAX_BPS as u32, TicketError::InvalidSplit);
+    Ok(())
+}
+
+/// Accept only https://, ipfs:// or ar:// URIs that fit in the ticket.
+fn validate_metadata_uri(uri: &str) -> Result<()> {
+    check_len("metadata_uri", uri, Ticket::MAX_METADATA_URI_LEN)?;
+    let has_location = ["https://", "ipfs://", "ar://"]
+        .iter()
+        .any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme));
+    require!(has_location, TicketError::InvalidUri);
+    Ok(())
+}
+
+/// Fail with `TooManyAccounts` if more than `MAX_REMAINING_ACCOUNTS` were
+/// passed, rather than running out of compute part-way through them.
+fn check_remaining_accounts(accounts: &[AccountInfo]) -> Result<()> {
+    if accounts.len() > MAX_REMAINING_ACCOUNTS {
+        msg!(
+            "{} remaining accounts passed (max {})",
+            accounts.len(),
+            MAX_REMAINING_ACCOUNTS
+        );
+        return err!(TicketError::TooManyAccounts);
+    }
+    Ok(())
+}
+
+/// Reject a string longer than `max` bytes, naming the field in the logs.
+fn check_len(field: &str, value: &str, max: usize) -> Result<()> {
+    if value.len() > max {
+        msg!("Field {} is {} bytes (max {})", field, value.len(), max);
+        return err!(TicketError::FieldTooLong);
+    }
+    Ok(())
+}
+
+// --- PDA Helpers ---
+
+/// Allowlist leaf for a buyer: the SHA-256 of their pubkey.
+pub fn allowlist_leaf(buyer: &Pubkey) -> [u8; 32] {
+    hash(buyer.as_ref()).to_bytes()
+}
+
+/// Check a Merkle proof built with sorted-pair SHA-256 hashing
+/// (each parent is `hash(min(a, b) || max(a, b))`).
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
+
+/// Message a venue authority signs to authorize `redeem_with_signature`:
+/// the ticket address followed by its current `redeem_nonce` (little-endian).
+pub fn redeem_message(ticket: &Pubkey, redeem_nonce: u64) -> [u8; 40] {
+    let mut message = [0u8; 40];
+    message[..32].copy_from_slice(ticket.as_ref());
+    message[32..].copy_from_slice(&redeem_nonce.to_le_bytes());
+    message
+}
+
+/// Message a venue authority signs to issue a gate pass: `b"gate"`, then
+/// the ticket address and the holder it admits. Scanners check the pass
+/// signature over exactly these bytes.
+pub fn gate_pass_message(ticket: &Pubkey, holder: &Pubkey) -> [u8; 68] {
+    let mut message = [0u8; 68];
+    message[..4].copy_from_slice(b"gate");
+    message[4..36].copy_from_slice(ticket.as_ref());
+    message[36..].copy_from_slice(holder.as_ref());
+    message
+}
+
+/// SHA-256 digest of a string seed. PDA seeds are capped at 32 bytes, so
+/// free-form inputs like `event_id` are hashed first.
+pub fn seed_hash(value: &str) -> [u8; 32] {
+    hash(value.as_bytes()).to_bytes()
+}
+
+/// Identity seed for a ticket within its event. Seated tickets are keyed by
+/// seat alone, so a second purchase of the same seat collides with the
+/// existing PDA no matter who buys it. General admission tickets are keyed
+/// by attendee name and buyer.
+pub fn ticket_holder_seed(attendee_name: &str, seat: Option<&str>, buyer: &Pubkey) -> [u8; 32] {
+    match seat {
+        Some(seat) => hashv(&[b"seat", seat.as_bytes()]).to_bytes(),
+        None => hashv(&[attendee_name.as_bytes(), buyer.as_ref()]).to_bytes(),
+    }
+}
+
+/// Derive the ticket PDA exactly as `purchase_ticket` does, for client use.
+pub fn ticket_pda(
+    event_id: &str,
+    attendee_name: &str,
+    seat: Option<&str>,
+    buyer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"ticket",
+            seed_hash(event_id).as_ref(),
+            ticket_holder_seed(attendee_name, seat, buyer).as_ref(),
+        ],
+        &crate::ID,
+    )
+}
+
+/// Derive the `BlacklistEntry` PDA banning `wallet` from an event.
+pub fn blacklist_pda(event_id: &str, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"blacklist", seed_hash(event_id).as_ref(), wallet.as_ref()],
+        &crate::ID,
+    )
+}
+
+// --- Account Structures ---
+
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct BatchPurchase<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BuyerAllocation::SIZE,
+        seeds = [b"alloc", seed_hash(&event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+    )]
+    pub allocation: Account<'info, BuyerAllocation>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SIZE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// Program admin — the only signer allowed to change Config
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ TicketError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(authority: Pubkey)]
+pub struct RegisterVenue<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ TicketError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = VerifiedVenue::SIZE,
+        seeds = [b"venue", authority.as_ref()],
+        bump,
+    )]
+    pub verified_venue: Account<'info, VerifiedVenue>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(authority: Pubkey)]
+pub struct DeregisterVenue<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ TicketError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"venue", authority.as_ref()],
+        bump = verified_venue.bump,
+    )]
+    pub verified_venue: Account<'info, VerifiedVenue>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, venue: String, venue_authority: Pubkey)]
+pub struct CreateEvent<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = Event::SIZE,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Lamport escrow for this event's SOL proceeds, owned by the system program
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"venue", venue_authority.as_ref()],
+        bump = verified_venue.bump,
+    )]
+    pub verified_venue: Option<Account<'info, VerifiedVenue>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", organizer.key().as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct CloneEvent<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    /// Event whose settings are copied
+    #[account(has_one = organizer @ TicketError::UnauthorizedOrganizer)]
+    pub source: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = Event::SIZE,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Lamport escrow for the new event's SOL proceeds
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", organizer.key().as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateEvent<'info> {
+    /// Event organizer — the only signer allowed to change event settings
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct SetInsurancePremium<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance", event.key().as_ref()],
+        bump,
+    )]
+    pub insurance_pool: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitEventStats<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = EventStats::SIZE,
+        seeds = [b"stats", event.key().as_ref()],
+        bump,
+    )]
+    pub stats: AccountLoader<'info, EventStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = ReferralRewards::SIZE,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump,
+    )]
+    pub referral_rewards: Account<'info, ReferralRewards>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump = referral_rewards.bump,
+        has_one = referrer,
+    )]
+    pub referral_rewards: Account<'info, ReferralRewards>,
+}
+
+#[derive(Accounts)]
+pub struct DepositCredits<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = CreditBalance::SIZE,
+        seeds = [b"credits", owner.key().as_ref()],
+        bump,
+    )]
+    pub credits: Account<'info, CreditBalance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey, requirement: u8)]
+pub struct IssueAttestation<'info> {
+    #[account(mut)]
+    pub attester: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = attester,
+        space = Attestation::SIZE,
+        seeds = [b"attestation", attester.key().as_ref(), wallet.as_ref(), &[requirement]],
+        bump,
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey, requirement: u8)]
+pub struct RevokeAttestation<'info> {
+    #[account(mut)]
+    pub attester: Signer<'info>,
+
+    #[account(
+        mut,
+        close = attester,
+        seeds = [b"attestation", attester.key().as_ref(), wallet.as_ref(), &[requirement]],
+        bump = attestation.bump,
+    )]
+    pub attestation: Account<'info, Attestation>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCredits<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"credits", owner.key().as_ref()],
+        bump = credits.bump,
+        has_one = owner,
+    )]
+    pub credits: Account<'info, CreditBalance>,
+}
+
+#[derive(Accounts)]
+pub struct InitOrganizerProfile<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = OrganizerProfile::SIZE,
+        seeds = [b"organizer", organizer.key().as_ref()],
+        bump,
+    )]
+    pub profile: Account<'info, OrganizerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEventStatus<'info> {
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", organizer.key().as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+}
+
+#[derive(Accounts)]
+pub struct ManageEvent<'info> {
+    /// Organizer or a co-organizer; permissions are checked per instruction
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+}
+
+#[derive(Accounts)]
+pub struct AutoCancelEvent<'info> {
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, venue: String)]
+pub struct CreateVenueStop<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = VenueStop::SIZE,
+        seeds = [b"stop", seed_hash(&event_id).as_ref(), seed_hash(&venue).as_ref()],
+        bump,
+    )]
+    pub stop: Account<'info, VenueStop>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, wallet: Pubkey)]
+pub struct BlacklistAttendee<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = BlacklistEntry::SIZE,
+        seeds = [b"blacklist", seed_hash(&event_id).as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub entry: Account<'info, BlacklistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, wallet: Pubkey)]
+pub struct UnblacklistAttendee<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = organizer,
+        seeds = [b"blacklist", seed_hash(&event_id).as_ref(), wallet.as_ref()],
+        bump = entry.bump,
+    )]
+    pub entry: Account<'info, BlacklistEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, code_hash: [u8; 32])]
+pub struct CreatePromoCode<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = PromoCode::SIZE,
+        seeds = [b"promo", seed_hash(&event_id).as_ref(), code_hash.as_ref()],
+        bump,
+    )]
+    pub promo: Account<'info, PromoCode>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    event_id: String,
+    event_name: String,
+    attendee_name: String,
+    seat: Option<String>,
+)]
+pub struct PurchaseTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BuyerAllocation::SIZE,
+        seeds = [b"alloc", seed_hash(&event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+    )]
+    pub allocation: Account<'info, BuyerAllocation>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket",
+            seed_hash(&event_id).as_ref(),
+            ticket_holder_seed(&attendee_name, seat.as_deref(), &buyer.key()).as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    /// CHECK: Pyth SOL/USD `PriceUpdateV2` account, only needed by
+    /// `purchase_ticket_usd`. Owner and layout are checked when parsed.
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    /// Promo code, only needed by `purchase_ticket_with_promo`.
+    #[account(mut)]
+    pub promo: Option<Account<'info, PromoCode>>,
+
+    /// Event insurance pool, only needed for insured purchases.
+    #[account(
+        mut,
+        seeds = [b"insurance", event.key().as_ref()],
+        bump = event.insurance_bump,
+    )]
+    pub insurance_pool: Option<SystemAccount<'info>>,
+
+    /// Tour stop the ticket is for, required once the event has any
+    /// stops. The ticket takes its venue.
+    #[account(
+        mut,
+        seeds = [b"stop", seed_hash(&event_id).as_ref(), seed_hash(&stop.venue).as_ref()],
+        bump = stop.bump,
+    )]
+    pub stop: Option<Account<'info, VenueStop>>,
+
+    /// Referrer's reward account, required when a referrer is passed.
+    #[account(
+        mut,
+        seeds = [b"referral", referral_rewards.referrer.as_ref()],
+        bump = referral_rewards.bump,
+    )]
+    pub referral_rewards: Option<Account<'info, ReferralRewards>>,
+
+    /// Buyer's prepaid credits, spent by `purchase_ticket_with_credits`.
+    #[account(
+        mut,
+        seeds = [b"credits", buyer.key().as_ref()],
+        bump = credits.bump,
+    )]
+    pub credits: Option<Account<'info, CreditBalance>>,
+
+    /// Buyer's attestation from the event's attester, required on gated events.
+    #[account(
+        seeds = [
+            b"attestation",
+            event.attester.as_ref(),
+            buyer.key().as_ref(),
+            &[event.required_attestation],
+        ],
+        bump = attestation.bump,
+    )]
+    pub attestation: Option<Account<'info, Attestation>>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    event_id: String,
+    event_name: String,
+    attendee_name: String,
+    seat: Option<String>,
+)]
+pub struct PurchaseTicketSpl<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BuyerAllocation::SIZE,
+        seeds = [b"alloc", seed_hash(&event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+    )]
+    pub allocation: Account<'info, BuyerAllocation>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = buyer_token_account.owner == buyer.key() @ TicketError::InvalidTokenOwner,
+        constraint = buyer_token_account.mint == mint.key() @ TicketError::MintMismatch,
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Venue's destination token account — must hold the same mint as the payment
+    #[account(
+        mut,
+        constraint = venue_token_account.mint == mint.key() @ TicketError::MintMismatch,
+        constraint = venue_token_account.owner == event.venue_authority @ TicketError::VenueMismatch,
+    )]
+    pub venue_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket",
+            seed_hash(&event_id).as_ref(),
+            ticket_holder_seed(&attendee_name, seat.as_deref(), &buyer.key()).as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    /// Legacy Token program or Token-2022; must own `mint`
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Venue authority or one of the event's redemption delegates
+    pub venue_authority: Signer<'info>,
+
+    #[account(
+        mut,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+        constraint = event.is_redeemer(&ticket.venue_authority, &venue_authority.key())
+            @ TicketError::UnauthorizedRedemption,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+}
+
+#[derive(Accounts)]
+pub struct MintPoap<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: POAP recipient. Must be the ticket's owner.
+    #[account(address = ticket.owner @ TicketError::OwnerMismatch)]
+    pub poap_owner: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config PDA for `poap_tree`, checked by seeds.
+    #[account(
+        mut,
+        seeds = [poap_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    pub poap_tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: The event's POAP Merkle tree, checked by address.
+    #[account(mut, address = event.poap_tree @ TicketError::InvalidTreeConfig)]
+    pub poap_tree: UncheckedAccount<'info>,
+
+    /// CHECK: PDA set as the POAP tree's delegate; signs the mint.
+    #[account(seeds = [b"poap", event.key().as_ref()], bump)]
+    pub poap_authority: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program, checked by address.
+    #[account(address = bubblegum::SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program, checked by address.
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program, checked by address.
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemWithSignature<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// CHECK: Instructions sysvar, checked by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct IssueGatePass<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = GatePass::SIZE,
+        seeds = [b"gate_pass", ticket.key().as_ref()],
+        bump,
+    )]
+    pub gate_pass: Account<'info, GatePass>,
+
+    /// CHECK: Instructions sysvar, checked by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct SettleGatePasses<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Venue authority or one of the event's redemption delegates
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+}
+
+#[derive(Accounts)]
+#[instruction(pass_id: String, holder: Pubkey)]
+pub struct IssueSeasonPass<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = SeasonPass::SIZE,
+        seeds = [b"pass", organizer.key().as_ref(), seed_hash(&pass_id).as_ref(), holder.as_ref()],
+        bump,
+    )]
+    pub pass: Account<'info, SeasonPass>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPass<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Venue authority or one of the event's redemption delegates
+    pub venue_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pass: Account<'info, SeasonPass>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&event.event_id).as_ref()],
+        bump = event.bump,
+        constraint = event.is_redeemer(&event.venue_authority, &venue_authority.key())
+            @ TicketError::UnauthorizedRedemption,
+        constraint = event.organizer == pass.organizer @ TicketError::PassNotValidForEvent,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct BatchRedeem<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Venue authority or one of the event's redemption delegates
+    pub venue_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemAndBurn<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Venue authority or one of the event's redemption delegates
+    pub venue_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = ticket.ticket_status == TicketStatus::Active @ TicketError::TicketNotActive,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+        constraint = event.is_redeemer(&ticket.venue_authority, &venue_authority.key())
+            @ TicketError::UnauthorizedRedemption,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    // The accounts below are only needed when burning the cNFT.
+
+    /// cNFT holder — Bubblegum requires the leaf owner's signature to burn
+    pub leaf_owner: Option<Signer<'info>>,
+
+    /// CHECK: Leaf delegate, verified by Bubblegum against the leaf hash.
+    pub leaf_delegate: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum tree config PDA, verified by Bubblegum.
+    pub tree_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Merkle tree holding the cNFT. Bubblegum verifies the proof, and
+    /// the derived asset id is checked against `ticket.cnft_asset_id`.
+    #[account(mut)]
+    pub merkle_tree: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SPL Noop program, checked by address.
+    #[account(address = bubblegum::SPL_NOOP_ID)]
+    pub log_wrapper: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SPL Account Compression program, checked by address.
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum program, checked by address.
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_owner: Pubkey)]
+pub struct TransferTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Current owner, or the delegate it approved
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&ticket.event_id).as_ref(), new_owner.as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResellTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+        constraint = config.feature_enabled(Config::FEATURE_RESALE) @ TicketError::FeatureDisabled,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Current owner, or the delegate it approved
+    pub authority: Signer<'info>,
+
+    /// CHECK: Current owner — receives the sale proceeds minus royalties.
+    #[account(
+        mut,
+        address = ticket.owner @ TicketError::OwnerMismatch,
+    )]
+    pub seller: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&ticket.event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Royalty recipient. Must be the event organizer.
+    #[account(
+        mut,
+        constraint = organizer.key() == event.organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub organizer: AccountInfo<'info>,
+
+    /// CHECK: Original-buyer royalty recipient. Only needed when the event
+    /// pays one and the seller isn't the original buyer.
+    #[account(
+        mut,
+        address = ticket.original_buyer @ TicketError::OriginalBuyerMismatch,
+    )]
+    pub original_buyer: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+}
+
+#[derive(Accounts)]
+pub struct ListTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+        constraint = config.feature_enabled(Config::FEATURE_RESALE) @ TicketError::FeatureDisabled,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Ticket owner — pays the listing rent and receives the sale proceeds
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = Listing::SIZE,
+        seeds = [b"listing", ticket.key().as_ref()],
+        bump,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyListing<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+        constraint = config.feature_enabled(Config::FEATURE_RESALE) @ TicketError::FeatureDisabled,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Receives the sale proceeds and the listing rent. Must be the
+    /// listing's seller.
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing", ticket.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller @ TicketError::OwnerMismatch,
+        has_one = ticket,
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&ticket.event_id).as_ref(), buyer.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Royalty recipient. Must be the event organizer.
+    #[account(
+        mut,
+        constraint = organizer.key() == event.organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub organizer: AccountInfo<'info>,
+
+    /// CHECK: Original-buyer royalty recipient. Only needed when the event
+    /// pays one and the seller isn't the original buyer.
+    #[account(
+        mut,
+        address = ticket.original_buyer @ TicketError::OriginalBuyerMismatch,
+    )]
+    pub original_buyer: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    // The accounts below are only needed to burn and remint the cNFT.
+
+    /// CHECK: Program PDA acting as the cNFT's leaf delegate and the tree's
+    /// delegate; signed for with its seeds.
+    #[account(seeds = [b"cnft_authority"], bump)]
+    pub cnft_authority: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum tree config PDA, verified by Bubblegum.
+    #[account(mut)]
+    pub tree_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Merkle tree holding the cNFT. Bubblegum verifies the proof, and
+    /// the derived asset id is checked against `ticket.cnft_asset_id`.
+    #[account(mut)]
+    pub merkle_tree: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SPL Noop program, checked by address.
+    #[account(address = bubblegum::SPL_NOOP_ID)]
+    pub log_wrapper: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SPL Account Compression program, checked by address.
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum program, checked by address.
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DelistTicket<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller @ TicketError::OwnerMismatch,
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+        constraint = config.feature_enabled(Config::FEATURE_RESALE) @ TicketError::FeatureDisabled,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Bidder — funds the escrow and the offer rent
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = Offer::SIZE,
+        seeds = [b"offer", ticket.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+        constraint = config.feature_enabled(Config::FEATURE_RESALE) @ TicketError::FeatureDisabled,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Current ticket owner — receives the offer minus fees
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Receives the offer rent back. Must be the offer's bidder.
+    #[account(mut)]
+    pub bidder: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"offer", ticket.key().as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+        has_one = bidder,
+        has_one = ticket,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&ticket.event_id).as_ref(), bidder.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Royalty recipient. Must be the event organizer.
+    #[account(
+        mut,
+        constraint = organizer.key() == event.organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub organizer: AccountInfo<'info>,
+
+    /// CHECK: Original-buyer royalty recipient. Only needed when the event
+    /// pays one and the seller isn't the original buyer.
+    #[account(
+        mut,
+        address = ticket.original_buyer @ TicketError::OriginalBuyerMismatch,
+    )]
+    pub original_buyer: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+    )]
+    pub offer: Account<'info, Offer>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    /// Ticket holder raising the dispute — pays for the Dispute PDA
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = ticket.owner == owner.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Dispute::SIZE,
+        seeds = [b"dispute", ticket.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.arbiter == arbiter.key() @ TicketError::UnauthorizedArbiter,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", ticket.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Refund destination. Must match the ticket's current owner.
+    #[account(
+        mut,
+        constraint = owner.key() == ticket.owner @ TicketError::OwnerMismatch,
+    )]
+    pub owner: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Event organizer or config arbiter
+    #[account(
+        constraint = authority.key() == event.organizer
+            || authority.key() == config.arbiter @ TicketError::UnauthorizedFreeze,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterInterest<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub entrant: Signer<'info>,
+
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = entrant,
+        space = Entry::SIZE,
+        seeds = [b"entry", event.key().as_ref(), entrant.key().as_ref()],
+        bump,
+    )]
+    pub entry: Account<'info, Entry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitLottery<'info> {
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: Switchboard randomness account, owner and layout checked when parsed.
+    pub randomness: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DrawWinners<'info> {
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: Must be the randomness account committed for this lottery.
+    pub randomness: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, event_name: String, attendee_name: String, seat: Option<String>)]
+pub struct ClaimLotteryTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub entrant: Signer<'info>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = entrant,
+        seeds = [b"entry", event.key().as_ref(), entrant.key().as_ref()],
+        bump = entry.bump,
+    )]
+    pub entry: Account<'info, Entry>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&event_id).as_ref(), entrant.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = entrant,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket",
+            seed_hash(&event_id).as_ref(),
+            ticket_holder_seed(&attendee_name, seat.as_deref(), &entrant.key()).as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String)]
+pub struct JoinWaitlist<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub entrant: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = entrant,
+        space = WaitlistEntry::SIZE,
+        seeds = [b"waitlist", event.key().as_ref(), &event.waitlist_next.to_le_bytes()],
+        bump,
+    )]
+    pub entry: Account<'info, WaitlistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PromoteFromWaitlist<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone cranking the waitlist — pays the ticket's rent
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = entrant,
+        seeds = [b"waitlist", event.key().as_ref(), &entry.sequence.to_le_bytes()],
+        bump = entry.bump,
+    )]
+    pub entry: Account<'info, WaitlistEntry>,
+
+    /// CHECK: Receives the entry's rent. Must be the entrant.
+    #[account(
+        mut,
+        address = entry.entrant @ TicketError::OwnerMismatch,
+    )]
+    pub entrant: AccountInfo<'info>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&event.event_id).as_ref(), entry.entrant.as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket",
+            seed_hash(&event.event_id).as_ref(),
+            ticket_holder_seed(&entry.attendee_name, None, &entry.entrant).as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveWaitlist<'info> {
+    #[account(mut)]
+    pub entrant: Signer<'info>,
+
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = entrant,
+        has_one = entrant,
+        seeds = [b"waitlist", event.key().as_ref(), &entry.sequence.to_le_bytes()],
+        bump = entry.bump,
+    )]
+    pub entry: Account<'info, WaitlistEntry>,
+}
+
+#[derive(Accounts)]
+pub struct RefundWaitlistDeposit<'info> {
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = entrant,
+        seeds = [b"waitlist", event.key().as_ref(), &entry.sequence.to_le_bytes()],
+        bump = entry.bump,
+    )]
+    pub entry: Account<'info, WaitlistEntry>,
+
+    /// CHECK: Receives the deposit and rent. Must be the entrant.
+    #[account(
+        mut,
+        address = entry.entrant @ TicketError::OwnerMismatch,
+    )]
+    pub entrant: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepDepositRefunds<'info> {
+    pub organizer: Signer<'info>,
+
+    #[account(has_one = organizer @ TicketError::UnauthorizedOrganizer)]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct RefundEntry<'info> {
+    #[account(mut)]
+    pub entrant: Signer<'info>,
+
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        close = entrant,
+        seeds = [b"entry", event.key().as_ref(), entrant.key().as_ref()],
+        bump = entry.bump,
+    )]
+    pub entry: Account<'info, Entry>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTicket<'info> {
+    /// Venue authority — approves the cancellation and keeps any
+    /// cancellation fee
+    #[account(mut)]
+    pub venue: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = ticket.venue_authority == venue.key() @ TicketError::UnauthorizedCancellation,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Refund destination. Must match the ticket's current owner.
+    #[account(
+        mut,
+        constraint = owner.key() == ticket.owner @ TicketError::OwnerMismatch,
+    )]
+    pub owner: AccountInfo<'info>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncTicketDate<'info> {
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    /// Current ticket holder — receives the refund
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = ticket.owner == owner.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Receives any cancellation fee. Must be the event's venue authority.
+    #[account(
+        mut,
+        constraint = venue.key() == event.venue_authority @ TicketError::VenueMismatch,
+    )]
+    pub venue: AccountInfo<'info>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRefund<'info> {
+    /// Current ticket holder — receives the refund
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = ticket.owner == owner.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Receives any cancellation fee. Must be the event's venue authority.
+    #[account(
+        mut,
+        constraint = venue.key() == event.venue_authority @ TicketError::VenueMismatch,
+    )]
+    pub venue: AccountInfo<'info>,
+
+    /// Event insurance pool, only needed for insured tickets.
+    #[account(
+        mut,
+        seeds = [b"insurance", event.key().as_ref()],
+        bump = event.insurance_bump,
+    )]
+    pub insurance_pool: Option<SystemAccount<'info>>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PartialRefund<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub organizer: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Receives the refund. Must be the ticket's current owner.
+    #[account(
+        mut,
+        address = ticket.owner @ TicketError::OwnerMismatch,
+    )]
+    pub owner: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ChangeTier<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Current ticket holder — pays an upgrade, receives a downgrade refund
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = ticket.owner == owner.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Platform fee recipient on upgrades. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    /// CHECK: Receives any cancellation fee on downgrades. Must be the event's venue authority.
+    #[account(
+        mut,
+        constraint = venue.key() == event.venue_authority @ TicketError::VenueMismatch,
+    )]
+    pub venue: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseProceeds<'info> {
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// CHECK: Payout destination. Must be the event's venue authority.
+    #[account(
+        mut,
+        constraint = venue.key() == event.venue_authority @ TicketError::VenueMismatch,
+    )]
+    pub venue: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceeds<'info> {
+    /// Organizer or a co-organizer with `CAN_WITHDRAW`
+    pub authority: Signer<'info>,
+
+    /// CHECK: Proceeds destination. Must be the event's organizer.
+    #[account(
+        mut,
+        address = event.organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub organizer: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: String, event_name: String, attendee_name: String, seat: Option<String>)]
+pub struct IssueClaimableTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket",
+            seed_hash(&event_id).as_ref(),
+            ticket_holder_seed(&attendee_name, seat.as_deref(), &organizer.key()).as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    event_id: String,
+    event_name: String,
+    attendee_name: String,
+    seat: Option<String>,
+    customer: Pubkey,
+)]
+pub struct ReserveTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = organizer,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket",
+            seed_hash(&event_id).as_ref(),
+            ticket_holder_seed(&attendee_name, seat.as_deref(), &customer).as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmReservation<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Customer the ticket is held for — pays the held price
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// CHECK: Platform fee recipient. Must be the Config treasury.
+    #[account(
+        mut,
+        address = config.treasury @ TicketError::TreasuryMismatch,
+    )]
+    pub fee_destination: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = ticket.owner == customer.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", event.key().as_ref()],
+        bump = event.escrow_bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Zero-copy stats for the event, updated when passed.
+    #[account(
+        mut,
+        constraint = stats.load()?.event == event.key() @ TicketError::StatsMismatch,
+    )]
+    pub stats: Option<AccountLoader<'info, EventStats>>,
+
+    /// Organizer's dashboard totals, updated when passed.
+    #[account(
+        mut,
+        seeds = [b"organizer", event.organizer.as_ref()],
+        bump = profile.bump,
+    )]
+    pub profile: Option<Account<'info, OrganizerProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireReservation<'info> {
+    /// CHECK: Receives the held ticket's rent. Must be the event organizer.
+    #[account(
+        mut,
+        address = event.organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub organizer: AccountInfo<'info>,
+
+    #[account(mut, close = organizer)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// New owner — proves knowledge of the claim code
+    pub claimant: Signer<'info>,
+
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    /// CHECK: Must not exist — its presence means the wallet is banned.
+    #[account(
+        seeds = [b"blacklist", seed_hash(&ticket.event_id).as_ref(), claimant.key().as_ref()],
+        bump,
+        constraint = blacklist.data_is_empty() @ TicketError::AttendeeBlacklisted,
+    )]
+    pub blacklist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MergeTickets<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Owner of both tickets — receives the closed ticket's rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub target: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        close = owner,
+        constraint = source.key() != target.key() @ TicketError::TicketsNotMergeable,
+    )]
+    pub source: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&target.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct SplitTicket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ TicketError::ProgramPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Owner of the group ticket — pays for the new tickets
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub source: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&source.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimTicket<'info> {
+    /// Event organizer — receives the reclaimed rent
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+
+    #[account(mut, close = organizer)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+        has_one = organizer @ TicketError::UnauthorizedOrganizer,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTicket<'info> {
+    /// Ticket holder — receives the reclaimed rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        constraint = ticket.owner == owner.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTicket<'info> {
+    /// Ticket owner or event organizer — pays for the extra rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Ticket account in an older, shorter layout, so it can't be
+    /// deserialized as `Ticket`. Checked for program ownership here and for
+    /// the Ticket discriminator in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub ticket: UncheckedAccount<'info>,
+
+    /// The ticket's event, checked against its PDA in the handler
+    pub event: Account<'info, Event>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MarkNoShow<'info> {
+    #[account(mut)]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitRating<'info> {
+    /// Ticket holder — pays for the Rating PDA
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = ticket.owner == owner.key() @ TicketError::OwnerMismatch,
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        mut,
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Rating::SIZE,
+        seeds = [b"rating", ticket.key().as_ref()],
+        bump,
+    )]
+    pub rating: Account<'info, Rating>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetTicketInfo<'info> {
+    pub ticket: Account<'info, Ticket>,
+
+    #[account(
+        seeds = [b"event", seed_hash(&ticket.event_id).as_ref()],
+        bump = event.bump,
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct GetAttendanceView<'info> {
+    pub event: Account<'info, Event>,
+}
+
+// --- Data Structures ---
+
+/// One step of an event's group discounts: a batch of at least `min_qty`
+/// tickets gets `discount_bps` off each.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GroupDiscount {
+    pub min_qty: u8,
+    pub discount_bps: u16,
+}
+
+/// One step of an event's refund policy: cancelling more than
+/// `seconds_before` the event refunds `refund_bps` of the refundable amount.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RefundTier {
+    pub seconds_before: i64,
+    pub refund_bps: u16,
+}
+
+/// An event's oversubscription lottery for one tier (see `open_lottery`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Lottery {
+    pub tier: TicketTier,
+    pub registration_end: i64,
+    /// Lamports each entrant escrows: the tier price.
+    pub deposit: u64,
+    pub entrant_count: u32,
+    /// Switchboard randomness account committed for the draw.
+    pub randomness: Pubkey,
+    /// Revealed randomness; all zero until drawn.
+    pub seed: [u8; 32],
+    pub winners: u32,
+    /// Entry `i` wins when `(stride * i + offset) % entrant_count < winners`.
+    pub stride: u32,
+    pub offset: u32,
+    pub drawn: bool,
+}
+
+impl Lottery {
+    pub const SIZE: usize = 1 + 8 + 8 + 4 + 32 + 32 + 4 + 4 + 4 + 1;
+
+    /// Fix the draw from the revealed seed. The map `i -> (stride * i + offset)
+    /// % n` is a permutation of the entries, so exactly `winners` of them win,
+    /// and anyone can recompute it from `seed`.
+    pub fn draw(&mut self, seed: [u8; 32], available: u32) {
+        let n = self.entrant_count.max(1) as u64;
+        let mut stride = u64::from_le_bytes(seed[..8].try_into().unwrap()) % n;
+        while gcd(stride, n) != 1 {
+            stride = stride % n + 1;
+        }
+        self.offset = (u64::from_le_bytes(seed[8..16].try_into().unwrap()) % n) as u32;
+        self.stride = stride as u32;
+        self.seed = seed;
+        self.winners = available.min(self.entrant_count);
+        self.drawn = true;
+    }
+
+    /// Whether the entry registered at `index` was drawn.
+    pub fn is_winner(&self, index: u32) -> bool {
+        if !self.drawn || index >= self.entrant_count {
+            return false;
+        }
+        let n = self.entrant_count as u64;
+        let position = (self.stride as u64 * index as u64 + self.offset as u64) % n;
+        position < self.winners as u64
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// A co-promoter's share of an event's SOL proceeds.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PayoutSplit {
+    pub recipient: Pubkey,
+    pub bps: u16,
+}
+
+/// A co-organizer and the privileged actions they may take on an event.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CoOrganizer {
+    pub key: Pubkey,
+    pub permissions: u8,
+}
+
+impl CoOrganizer {
+    pub const CAN_RESCHEDULE: u8 = 1 << 0;
+    pub const CAN_CANCEL: u8 = 1 << 1;
+    pub const CAN_WITHDRAW: u8 = 1 << 2;
+    pub const ALL_PERMISSIONS: u8 = Self::CAN_RESCHEDULE | Self::CAN_CANCEL | Self::CAN_WITHDRAW;
+}
+
+/// One ticket in a `batch_purchase`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AttendeeInfo {
+    pub attendee_name: String,
+    pub seat: Option<String>,
+}
+
+/// One single ticket broken off a group ticket by `split_ticket`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SplitRecipient {
+    pub owner: Pubkey,
+    pub attendee_name: String,
+}
+
+/// Program-wide settings, stored in a singleton PDA at `[b"config"]`.
+#[account]
+pub struct Config {
+    pub admin: Pubkey,          // 32 bytes
+    pub paused: bool,           // 1 byte
+    pub platform_fee_bps: u16,  // 2 bytes
+    pub treasury: Pubkey,       // 32 bytes
+    pub arbiter: Pubkey,        // 32 bytes (default pubkey = no arbiter)
+    pub require_verified_venues: bool, // 1 byte
+    pub feature_flags: u64,     // 8 bytes (bitfield of Config::FEATURE_*; set = enabled)
+    pub version: u16,           // 2 bytes (program version this deployment runs)
+    pub bump: u8,               // 1 byte
+}
+
+impl Config {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 1 + 2 + 32 + 32 + 1 + 8 + 2 + 1;
+
+    /// Secondary sales: `resell_ticket`, listings and offers.
+    pub const FEATURE_RESALE: u64 = 1 << 0;
+    /// Allowlist presales via `purchase_ticket_presale`.
+    pub const FEATURE_PRESALE: u64 = 1 << 1;
+    /// Oracle-priced purchases via `purchase_ticket_usd`.
+    pub const FEATURE_ORACLE_PRICING: u64 = 1 << 2;
+    /// Features enabled on a fresh Config. Bits added later start off, so
+    /// new code can ship dark.
+    pub const DEFAULT_FEATURES: u64 =
+        Self::FEATURE_RESALE | Self::FEATURE_PRESALE | Self::FEATURE_ORACLE_PRICING;
+    /// Version written by `initialize_config`.
+    pub const CURRENT_VERSION: u16 = 1;
+
+    pub fn feature_enabled(&self, flag: u64) -> bool {
+        self.feature_flags & flag == flag
+    }
+
+    /// Fail with `FeatureDisabled` unless `flag` is on.
+    pub fn require_feature(&self, flag: u64) -> Result<()> {
+        require!(self.feature_enabled(flag), TicketError::FeatureDisabled);
+        Ok(())
+    }
+}
+
+/// A venue vouched for by the program admin, at `[b"venue", authority]`.
+/// Events whose venue authority has one, under the same `name`, are marked
+/// `venue_verified`.
+#[account]
+pub struct VerifiedVenue {
+    pub authority: Pubkey,      // 32 bytes (the venue's wallet)
+    pub name: String,           // 4 + 64 bytes (canonical venue name)
+    pub verified_at: i64,       // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl VerifiedVenue {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + (4 + Ticket::MAX_VENUE_LEN) + 8 + 1;
+}
+
+/// Short text fields are `FixedStr`s rather than `String`s, so each one
+/// costs its reserved bytes plus a single length byte. Before v15 they were
+/// `String`s; `layout` tells the two apart, and `migrate_ticket` rewrites
+/// the old head (see `LegacyTicketText`).
+#[account]
+pub struct Ticket {
+    pub layout: u8,             // 1 byte (always LAYOUT_TAG; see below)
+    pub event_id: FixedStr<128>, // 1 + 128 bytes
+    pub event_name: FixedStr<128>, // 1 + 128 bytes
+    pub event_date: i64,        // 8 bytes
+    pub venue: FixedStr<64>,    // 1 + 64 bytes
+    pub attendee_name: FixedStr<64>, // 1 + 64 bytes
+    pub seat: Option<FixedStr<16>>, // 1 + 1 + 16 bytes (None = general admission)
+    pub venue_authority: Pubkey, // 32 bytes
+    pub price_paid: u64,        // 8 bytes
+    pub platform_fee: u64,      // 8 bytes (non-refundable part of price_paid)
+    pub tier: TicketTier,       // 1 byte
+    pub transferable: bool,     // 1 byte (false = soulbound)
+    pub metadata_uri: String,   // 4 + 200 bytes
+    pub redeem_nonce: u64,      // 8 bytes
+    pub scan_count: u16,        // 2 bytes
+    pub last_scanned_at: i64,   // 8 bytes (0 = never scanned)
+    pub claim_hash: [u8; 32],   // 32 bytes (zero = not claimable)
+    pub claim_deadline: i64,    // 8 bytes
+    pub payment_mint: Pubkey,   // 32 bytes (default pubkey = native SOL)
+    pub ticket_status: TicketStatus, // 1 byte
+    pub cnft_asset_id: Pubkey,  // 32 bytes
+    pub owner: Pubkey,          // 32 bytes
+    pub created_at: i64,        // 8 bytes
+    pub bump: u8,               // 1 byte
+    // Fields below were added after launch. Only ever append here, so
+    // `migrate_ticket` can grow older accounts in place.
+    pub transfer_history: Vec<Pubkey>, // 4 + 32 * 8 bytes (oldest first)
+    pub transfer_count: u8,     // 1 byte
+    pub version: u8,            // 1 byte (0 = written before versioning, i.e. v1)
+    pub insured: bool,          // 1 byte (refund protection bought at checkout)
+    pub poap_asset_id: Pubkey,  // 32 bytes (default pubkey = no POAP minted)
+    pub frozen: bool,           // 1 byte (blocks transfer, redeem and refund)
+    pub venue_verified: bool,   // 1 byte (copied from the event at issue)
+    pub reserved_until: i64,    // 8 bytes (hold deadline while Reserved)
+    pub refunded_total: u64,    // 8 bytes (goodwill refunds paid so far)
+    pub accepted_terms: [u8; 32], // 32 bytes (event terms_hash the buyer agreed to)
+    pub day_checked_in: u8,     // 1 byte (bit i = admitted on festival day i)
+    pub original_buyer: Pubkey, // 32 bytes (first owner; kept across transfers)
+    pub delegate: Option<Pubkey>, // 1 + 32 bytes (may transfer on the owner's behalf)
+    pub delegate_expiry: i64,   // 8 bytes (delegate approval lapses after this)
+    pub cancel_reason: Option<CancelReason>, // 1 + 1 bytes (set once, when Cancelled)
+    pub quantity: u32,          // 4 bytes (people admitted; 0 = written before v14, i.e. 1)
+}
+
+// 1280 bytes with `String` text fields; keep an eye on rent per ticket.
+const _: () = assert!(Ticket::SIZE == 1266);
+
+impl Ticket {
+    pub const MAX_EVENT_ID_LEN: usize = 128;
+    pub const MAX_EVENT_NAME_LEN: usize = 128;
+    pub const MAX_VENUE_LEN: usize = 64;
+    pub const MAX_ATTENDEE_NAME_LEN: usize = 64;
+    pub const MAX_SEAT_LEN: usize = 16;
+    pub const MAX_METADATA_URI_LEN: usize = 200;
+    pub const MAX_TRANSFER_HISTORY: usize = 8;
+    /// Layout version written by this program. v2 added `transfer_history`,
+    /// `transfer_count` and `version` itself; v3 added `insured`; v4 added
+    /// `poap_asset_id`; v5 added `frozen`; v6 added `venue_verified`; v7
+    /// added `reserved_until`; v8 added `refunded_total`; v9 added
+    /// `accepted_terms`; v10 added `day_checked_in`; v11 added
+    /// `original_buyer`; v12 added `delegate` and `delegate_expiry`; v13
+    /// added `cancel_reason`; v14 added `quantity`; v15 stored the text
+    /// fields as `FixedStr` behind `layout`.
+    pub const CURRENT_VERSION: u8 = 15;
+    /// `layout` of a v15+ ticket. A `String`-layout ticket has the low byte
+    /// of its `event_id` length there instead, which never exceeds
+    /// `MAX_EVENT_ID_LEN`.
+    pub const LAYOUT_TAG: u8 = u8::MAX;
+
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8
+        + 1
+        + (1 + Self::MAX_EVENT_ID_LEN)
+        + (1 + Self::MAX_EVENT_NAME_LEN)
+        + 8
+        + (1 + Self::MAX_VENUE_LEN)
+        + (1 + Self::MAX_ATTENDEE_NAME_LEN)
+        + (1 + 1 + Self::MAX_SEAT_LEN)
+        + 32
+        + 8 + 8 + 1 + 1
+        + (4 + Self::MAX_METADATA_URI_LEN)
+        + 8
+        + 2 + 8
+        + 32 + 8
+        + 32 + 1 + 32 + 32 + 8 + 1
+        + (4 + 32 * Self::MAX_TRANSFER_HISTORY)
+        + 1 + 1 + 1
+        + 32 + 1 + 1
+        + 8 + 8
+        + 32 + 1
+        + 32
+        + (1 + 32) + 8
+        + (1 + 1)
+        + 4;
+
+    /// Decode a ticket account's `data` in any older layout: a `String`
+    /// head is rewritten as `FixedStr`s, and fields appended since are
+    /// zero-padded. Fails with `InvalidTicketAccount` if it isn't a ticket.
+    pub fn decode_any_version(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() > 8 && data[..8] == <Ticket as anchor_lang::Discriminator>::DISCRIMINATOR,
+            TicketError::InvalidTicketAccount
+        );
+        let mut padded = if data[8] == Self::LAYOUT_TAG {
+            data.to_vec()
+        } else {
+            let mut rest = &data[8..];
+            let text = LegacyTicketText::deserialize(&mut rest)
+                .map_err(|_| TicketError::InvalidTicketAccount)?;
+            let head = (
+                Self::LAYOUT_TAG,
+                FixedStr::<128>::try_from(text.event_id)?,
+                FixedStr::<128>::try_from(text.event_name)?,
+                text.event_date,
+                FixedStr::<64>::try_from(text.venue)?,
+                FixedStr::<64>::try_from(text.attendee_name)?,
+                text.seat.map(FixedStr::<16>::try_from).transpose()?,
+            );
+            let mut upgraded = data[..8].to_vec();
+            head.serialize(&mut upgraded)?;
+            upgraded.extend_from_slice(rest);
+            upgraded
+        };
+        padded.resize(padded.len().max(Self::SIZE), 0);
+        Self::try_deserialize_unchecked(&mut &padded[..])
+            .map_err(|_| TicketError::InvalidTicketAccount.into())
+    }
+
+    /// Hand the ticket to `new_owner`, remembering the previous owner in
+    /// `transfer_history` and dropping the oldest entry once full. Fails once
+    /// the ticket has moved `event.max_transfers` times (`u8::MAX` =
+    /// unlimited), or once it has expired (see `effective_status`).
+    pub fn record_transfer(&mut self, new_owner: Pubkey, event: &Event, now: i64) -> Result<()> {
+        require!(!self.frozen, TicketError::TicketFrozen);
+        require!(
+            self.effective_status(event, now) != TicketStatus::NoShow,
+            TicketError::TicketExpired
+        );
+        let max_transfers = event.max_transfers;
+        require!(
+            max_transfers == u8::MAX || self.transfer_count < max_transfers,
+            TicketError::TransferLimitReached
+        );
+        self.transfer_count = self.transfer_count.saturating_add(1);
+        if self.transfer_history.len() >= Self::MAX_TRANSFER_HISTORY {
+            self.transfer_history.remove(0);
+        }
+        self.transfer_history.push(self.owner);
+        self.owner = new_owner;
+        self.delegate = None;
+        self.delegate_expiry = 0;
+        Ok(())
+    }
+
+    /// Fail unless `signer` is the owner, or the approved delegate before
+    /// `delegate_expiry` (`DelegateExpired` after it).
+    pub fn check_transfer_authority(&self, signer: &Pubkey, now: i64) -> Result<()> {
+        if *signer == self.owner {
+            return Ok(());
+        }
+        require!(
+            self.delegate.as_ref() == Some(signer),
+            TicketError::UnauthorizedTransfer
+        );
+        require!(now <= self.delegate_expiry, TicketError::DelegateExpired);
+        Ok(())
+    }
+
+    /// Status as of `now`. An Active ticket whose event's redemption window,
+    /// late-entry grace included, has closed can no longer be used, so it
+    /// reads as NoShow, the status `mark_no_show` would store for it.
+    /// Tickets to a cancelled event stay Active so they can be refunded.
+    pub fn effective_status(&self, event: &Event, now: i64) -> TicketStatus {
+        if self.ticket_status == TicketStatus::Active
+            && event.status != EventStatus::Cancelled
+            && now > event.redemption_end()
+        {
+            TicketStatus::NoShow
+        } else {
+            self.ticket_status.clone()
+        }
+    }
+
+    /// Whether the ticket has already been admitted on `day` of `event`: on
+    /// a multi-day event, that day's `day_checked_in` bit; otherwise, at all.
+    pub fn is_checked_in(&self, event: &Event, day: u8) -> bool {
+        if event.days > 1 {
+            day < Event::MAX_DAYS && self.day_checked_in & (1 << day) != 0
+        } else {
+            self.ticket_status == TicketStatus::Redeemed
+        }
+    }
+
+    /// Fail with `TicketNotTransferable` unless the ticket is Active and was
+    /// issued as transferable.
+    pub fn check_transferable(&self) -> Result<()> {
+        require!(
+            self.ticket_status == TicketStatus::Active && self.transferable,
+            TicketError::TicketNotTransferable
+        );
+        Ok(())
+    }
+
+    /// Issued by the organizer and still waiting for `claim_ticket`.
+    pub fn is_claimable(&self) -> bool {
+        self.owner == Pubkey::default() && self.claim_hash != [0u8; 32]
+    }
+
+    /// Fail unless the ticket can be claimed at `now`: claimable, and no
+    /// later than `claim_deadline` (`ClaimExpired` after it).
+    pub fn check_claim(&self, now: i64) -> Result<()> {
+        require!(self.is_claimable(), TicketError::TicketNotClaimable);
+        require!(now <= self.claim_deadline, TicketError::ClaimExpired);
+        Ok(())
+    }
+
+    /// Fail unless the organizer can reclaim the ticket at `now`: still
+    /// claimable, and past `claim_deadline` (`ClaimNotExpired` until then).
+    pub fn check_reclaim(&self, now: i64) -> Result<()> {
+        require!(self.is_claimable(), TicketError::TicketNotClaimable);
+        require!(now > self.claim_deadline, TicketError::ClaimNotExpired);
+        Ok(())
+    }
+
+    /// Mark the ticket Cancelled for `reason`. The reason is written once;
+    /// a ticket that already has one fails with `CancelReasonAlreadySet`.
+    pub fn cancel(&mut self, reason: CancelReason) -> Result<()> {
+        require!(
+            self.cancel_reason.is_none(),
+            TicketError::CancelReasonAlreadySet
+        );
+        self.ticket_status = TicketStatus::Cancelled;
+        self.cancel_reason = Some(reason);
+        Ok(())
+    }
+
+    /// How many people the ticket admits, and seats it holds.
+    pub fn admits(&self) -> u32 {
+        self.quantity.max(1)
+    }
+
+    /// Portion of `price_paid` held in escrow and returned on refund.
+    pub fn refundable_amount(&self) -> u64 {
+        self.price_paid
+            .saturating_sub(self.platform_fee)
+            .saturating_sub(self.refunded_total)
+    }
+
+    /// Check the ticket can move to `new_tier`: an Active, unfrozen,
+    /// lamport-paid ticket of a different tier. Redeemed tickets are
+    /// rejected with `TicketAlreadyRedeemed`.
+    pub fn check_tier_change(&self, new_tier: TicketTier) -> Result<()> {
+        require!(
+            self.ticket_status != TicketStatus::Redeemed,
+            TicketError::TicketAlreadyRedeemed
+        );
+        require!(
+            self.ticket_status == TicketStatus::Active,
+            TicketError::TicketNotActive
+        );
+        require!(!self.frozen, TicketError::TicketFrozen);
+        require!(
+            self.payment_mint == Pubkey::default(),
+            TicketError::MintMismatch
+        );
+        require!(
+            new_tier != self.tier && self.admits() == 1,
+            TicketError::InvalidTierChange
+        );
+        Ok(())
+    }
+
+    /// Reject strings that would not fit in the space allotted by `SIZE`.
+    pub fn validate_fields(
+        event_id: &str,
+        event_name: &str,
+        venue: &str,
+        attendee_name: &str,
+        seat: &Option<String>,
+    ) -> Result<()> {
+        check_len("event_id", event_id, Self::MAX_EVENT_ID_LEN)?;
+        check_len("event_name", event_name, Self::MAX_EVENT_NAME_LEN)?;
+        check_len("venue", venue, Self::MAX_VENUE_LEN)?;
+        check_len("attendee_name", attendee_name, Self::MAX_ATTENDEE_NAME_LEN)?;
+        if let Some(seat) = seat {
+            require!(!seat.is_empty(), TicketError::InvalidSeat);
+            check_len("seat", seat, Self::MAX_SEAT_LEN)?;
+        }
+        Ok(())
+    }
+}
+
+/// The head of a ticket written before v15, when its text fields were
+/// `String`s. Everything after `seat` is laid out the same either way.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyTicketText {
+    event_id: String,
+    event_name: String,
+    event_date: i64,
+    venue: String,
+    attendee_name: String,
+    seat: Option<String>,
+}
+
+/// Read-only snapshot of a ticket returned by `get_ticket_info`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TicketView {
+    pub event_name: String,
+    pub venue: String,
+    pub attendee_name: String,
+    pub event_date: i64,
+    /// Venue's local offset from UTC, for rendering `event_date`
+    pub tz_offset_minutes: i16,
+    pub price_paid: u64,
+    pub status: TicketStatus,
+    pub owner: Pubkey,
+    pub transfer_history: Vec<Pubkey>,
+}
+
+impl From<&Ticket> for TicketView {
+    fn from(ticket: &Ticket) -> Self {
+        Self {
+            event_name: ticket.event_name.to_string(),
+            venue: ticket.venue.to_string(),
+            attendee_name: ticket.attendee_name.to_string(),
+            event_date: ticket.event_date,
+            // Lives on the Event; `get_ticket_info` fills it in
+            tz_offset_minutes: 0,
+            price_paid: ticket.price_paid,
+            status: ticket.ticket_status.clone(),
+            owner: ticket.owner,
+            transfer_history: ticket.transfer_history.clone(),
+        }
+    }
+}
+
+/// Answer returned by `verify_ticket`. `reason` is one of the
+/// `TicketValidity` constants; `VALID` exactly when `valid` is true.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TicketValidity {
+    pub valid: bool,
+    pub reason: u8,
+}
+
+impl TicketValidity {
+    pub const VALID: u8 = 0;
+    pub const FROZEN: u8 = 1;
+    pub const ALREADY_REDEEMED: u8 = 2;
+    /// Cancelled, no-show, or reserved and not yet paid for
+    pub const NOT_ACTIVE: u8 = 3;
+    pub const EVENT_CANCELLED: u8 = 4;
+    /// Doors aren't open: the event isn't Live
+    pub const EVENT_NOT_LIVE: u8 = 5;
+    pub const TOO_EARLY: u8 = 6;
+    pub const TOO_LATE: u8 = 7;
+
+    /// Mirror the checks `redeem` makes, in the same order.
+    pub fn check(ticket: &Ticket, event: &Event, now: i64) -> Self {
+        let reason = if ticket.frozen {
+            Self::FROZEN
+        } else if ticket.is_checked_in(event, event.day_of(now))
+            && (event.days > 1 || !event.reentry_allowed)
+        {
+            Self::ALREADY_REDEEMED
+        } else if !matches!(
+            ticket.ticket_status,
+            TicketStatus::Active | TicketStatus::Redeemed
+        ) {
+            Self::NOT_ACTIVE
+        } else if event.status == EventStatus::Cancelled {
+            Self::EVENT_CANCELLED
+        } else if event.status != EventStatus::Live {
+            Self::EVENT_NOT_LIVE
+        } else if now < event.redeem_window_start {
+            Self::TOO_EARLY
+        } else if now > event.redemption_end() {
+            Self::TOO_LATE
+        } else {
+            Self::VALID
+        };
+        Self {
+            valid: reason == Self::VALID,
+            reason,
+        }
+    }
+}
+
+/// Attendance counters returned by `get_event_stats`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AttendanceView {
+    pub tickets_sold: u32,
+    pub redeemed_count: u32,
+    pub no_show_count: u32,
+    pub rating_sum: u64,
+    pub rating_count: u32,
+}
+
+impl From<&Event> for AttendanceView {
+    fn from(event: &Event) -> Self {
+        Self {
+            tickets_sold: event.tickets_sold,
+            redeemed_count: event.redeemed_count,
+            no_show_count: event.no_show_count,
+            rating_sum: event.rating_sum,
+            rating_count: event.rating_count,
+        }
+    }
+}
+
+#[account]
+pub struct Event {
+    pub event_id: String,       // 4 + 128 bytes
+    pub organizer: Pubkey,      // 32 bytes
+    pub venue: String,          // 4 + 64 bytes
+    pub event_date: i64,        // 8 bytes
+    pub capacity: u32,          // 4 bytes
+    pub tickets_sold: u32,      // 4 bytes
+    pub max_per_buyer: u8,      // 1 byte
+    pub redeem_window_start: i64, // 8 bytes
+    pub redeem_window_end: i64, // 8 bytes
+    pub tier_capacities: [u32; 3], // 4 * 3 bytes, indexed by TicketTier
+    pub tier_prices: [u64; 3],  // 8 * 3 bytes
+    pub tier_sold: [u32; 3],    // 4 * 3 bytes
+    pub max_resale_price: u64,  // 8 bytes
+    pub royalty_bps: u16,       // 2 bytes
+    pub venue_authority: Pubkey, // 32 bytes
+    pub escrow_bump: u8,        // 1 byte
+    pub status: EventStatus,    // 1 byte
+    pub rescheduled_from: i64,  // 8 bytes (0 = never rescheduled)
+    pub allowlist_root: Option<[u8; 32]>, // 1 + 32 bytes
+    pub presale_open: bool,     // 1 byte
+    pub sales_start: i64,       // 8 bytes
+    pub sales_end: i64,         // 8 bytes (0 = open until sold out)
+    pub reentry_allowed: bool,  // 1 byte
+    pub redemption_delegates: Vec<Pubkey>, // 4 + 32 * 16 bytes
+    pub price_usd_cents: u64,   // 8 bytes (0 = USD pricing off)
+    pub refund_policy: Vec<RefundTier>, // 4 + (8 + 2) * 4 bytes (empty = full refund)
+    pub max_transfers: u8,      // 1 byte (u8::MAX = unlimited)
+    pub lottery: Option<Lottery>, // 1 + Lottery::SIZE bytes
+    pub refund_deadline: i64,   // 8 bytes (0 = no voluntary refunds)
+    pub payout_splits: Vec<PayoutSplit>, // 4 + (32 + 2) * 5 bytes (empty = escrow)
+    pub early_bird_deadline: i64, // 8 bytes (0 = no early bird)
+    pub early_bird_bps: u16,    // 2 bytes
+    pub withdrawable: u64,      // 8 bytes (escrow the organizer may withdraw now)
+    pub locked: u64,            // 8 bytes (escrow backing unredeemed tickets)
+    pub redeemed_count: u32,    // 4 bytes (tickets scanned at least once)
+    pub no_show_count: u32,     // 4 bytes (tickets marked NoShow)
+    pub late_entry_grace: i64,  // 8 bytes (seconds after event_date; 0 = none)
+    pub insurance_premium: u64, // 8 bytes (0 = insurance not offered)
+    pub insurance_pool: u64,    // 8 bytes (premiums collected less payouts)
+    pub insurance_bump: u8,     // 1 byte
+    pub co_organizers: Vec<CoOrganizer>, // 4 + (32 + 1) * 8 bytes
+    pub poap_tree: Pubkey,      // 32 bytes (default pubkey = no POAPs)
+    pub venue_stops: u16,       // 2 bytes (0 = single venue, no VenueStop needed)
+    pub waitlist_next: u64,     // 8 bytes (sequence number of the next joiner)
+    pub waitlist_head: u64,     // 8 bytes (oldest sequence not yet promoted or gone)
+    pub pricing_mode: PricingMode, // 1 + 32 bytes
+    pub venue_verified: bool,   // 1 byte (venue authority had a VerifiedVenue at creation)
+    pub rating_sum: u64,        // 8 bytes (sum of attendee scores)
+    pub rating_count: u32,      // 4 bytes
+    pub referral_reward_bps: u16, // 2 bytes (share of the price paid to referrers)
+    pub referral_budget: u64,   // 8 bytes (lamports referrers can earn in total)
+    pub referral_paid: u64,     // 8 bytes (lamports referrers have earned)
+    pub tz_offset_minutes: i16, // 2 bytes (venue's local offset from UTC, display only)
+    pub currency: Currency,     // 1 + 32 bytes (what tickets are paid in)
+    pub attester: Pubkey,       // 32 bytes (who vouches for buyers on gated events)
+    pub required_attestation: u8, // 1 byte (Attestation requirement id; 0 = not gated)
+    pub terms_hash: [u8; 32],   // 32 bytes (hash of the current terms; zero = none)
+    pub days: u8,               // 1 byte (festival length; tickets admit once per day)
+    pub revenue_cap: u64,       // 8 bytes (0 = no cap)
+    pub gross_revenue: u64,     // 8 bytes (collected from buyers, gross of refunds)
+    pub group_discounts: Vec<GroupDiscount>, // 4 + (1 + 2) * 4 bytes (empty = none)
+    pub original_buyer_royalty_bps: u16, // 2 bytes (resale cut for the first purchaser)
+    pub min_tickets: u32,       // 4 bytes (sales needed for the show to go ahead; 0 = none)
+    pub confirm_by: i64,        // 8 bytes (deadline for reaching min_tickets)
+    pub confirmed: bool,        // 1 byte (min_tickets was reached)
+    pub event_name: String,     // 4 + 128 bytes (display name; empty = not set)
+    pub metadata_uri: String,   // 4 + 200 bytes
+    pub enforce_royalty_on_transfer: bool, // 1 byte (free transfers only to the allowlist)
+    pub min_resale_price: u64,  // 8 bytes (floor for resales while enforced)
+    pub free_transfer_recipients: Vec<Pubkey>, // 4 + 32 * 4 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl Event {
+    pub const MAX_DELEGATES: usize = 16;
+    pub const MAX_REFUND_TIERS: usize = 4;
+    pub const MAX_GROUP_DISCOUNTS: usize = 4;
+    pub const MAX_UPDATE_REASON_LEN: usize = 64;
+    pub const MAX_FREE_TRANSFER_RECIPIENTS: usize = 4;
+    /// Each split recipient adds one account to every sale and one System
+    /// transfer CPI per payment, the most expensive step of a purchase after
+    /// the ticket's own init: about 1,150 CU at the runtime's fixed prices
+    /// (1,000 to invoke, 150 for the System program), so five add under
+    /// 6,000 CU to a sale.
+    pub const MAX_PAYOUT_SPLITS: usize = 5;
+    pub const MAX_CO_ORGANIZERS: usize = 8;
+    /// Longest festival, bounded by the ticket's `day_checked_in` bitmask.
+    pub const MAX_DAYS: u8 = 8;
+    /// Widest real UTC offsets are −12:00 and +14:00.
+    pub const MAX_TZ_OFFSET_MINUTES: i16 = 840;
+
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8
+        + (4 + Ticket::MAX_EVENT_ID_LEN)
+        + 32
+        + (4 + Ticket::MAX_VENUE_LEN)
+        + 8 + 4 + 4 + 1 + 8 + 8
+        + (4 * 3) + (8 * 3) + (4 * 3)
+        + 8 + 2
+        + 32 + 1 + 1 + 8
+        + (1 + 32) + 1
+        + 8 + 8
+        + 1
+        + (4 + 32 * Self::MAX_DELEGATES)
+        + 8
+        + (4 + (8 + 2) * Self::MAX_REFUND_TIERS)
+        + 1
+        + (1 + Lottery::SIZE)
+        + 8
+        + (4 + (32 + 2) * Self::MAX_PAYOUT_SPLITS)
+        + 8 + 2
+        + 8 + 8
+        + 4 + 4
+        + 8
+        + 8 + 8 + 1
+        + (4 + (32 + 1) * Self::MAX_CO_ORGANIZERS)
+        + 32 + 2
+        + 8 + 8
+        + PricingMode::SIZE
+        + 1
+        + 8 + 4
+        + 2 + 8 + 8
+        + 2
+        + Currency::SIZE
+        + 32 + 1
+        + 32
+        + 1
+        + 8 + 8
+        + (4 + (1 + 2) * Self::MAX_GROUP_DISCOUNTS)
+        + 2
+        + 4 + 8 + 1
+        + (4 + Ticket::MAX_EVENT_NAME_LEN)
+        + (4 + Ticket::MAX_METADATA_URI_LEN)
+        + 1 + 8 + (4 + 32 * Self::MAX_FREE_TRANSFER_RECIPIENTS)
+        + 1;
+
+    /// A fresh event with this one's settings, for `clone_event`. Absolute
+    /// dates are shifted by the change in `event_date`; everything that
+    /// tracks sales or needs its own accounts is reset.
+    pub fn template_for(
+        &self,
+        event_id: String,
+        event_date: i64,
+        escrow_bump: u8,
+        bump: u8,
+    ) -> Event {
+        let delta = event_date.saturating_sub(self.event_date);
+        // 0 means "not set" for these, so leave it alone
+        let shift = |at: i64| if at == 0 { 0 } else { at.saturating_add(delta) };
+        Event {
+            event_id,
+            event_date,
+            tickets_sold: 0,
+            redeem_window_start: self.redeem_window_start.saturating_add(delta),
+            redeem_window_end: self.redeem_window_end.saturating_add(delta),
+            tier_sold: [0; 3],
+            escrow_bump,
+            status: EventStatus::Scheduled,
+            rescheduled_from: 0,
+            allowlist_root: None,
+            presale_open: false,
+            sales_start: shift(self.sales_start),
+            sales_end: shift(self.sales_end),
+            lottery: None,
+            refund_deadline: shift(self.refund_deadline),
+            early_bird_deadline: shift(self.early_bird_deadline),
+            withdrawable: 0,
+            locked: 0,
+            redeemed_count: 0,
+            no_show_count: 0,
+            insurance_premium: 0,
+            insurance_pool: 0,
+            insurance_bump: 0,
+            venue_stops: 0,
+            waitlist_next: 0,
+            waitlist_head: 0,
+            rating_sum: 0,
+            rating_count: 0,
+            referral_paid: 0,
+            gross_revenue: 0,
+            confirm_by: shift(self.confirm_by),
+            confirmed: false,
+            bump,
+            ..self.clone()
+        }
+    }
+
+    /// Referral reward on a sale at `price` whose platform fee is `fee`:
+    /// `referral_reward_bps` of the price, capped by the fee it is carved
+    /// out of and by what's left of `referral_budget`. Counted as paid.
+    pub fn take_referral_reward(&mut self, price: u64, fee: u64) -> Result<u64> {
+        let reward = math::fee_of(price, self.referral_reward_bps)?
+            .min(fee)
+            .min(self.referral_budget.saturating_sub(self.referral_paid));
+        self.referral_paid = math::add(self.referral_paid, reward)?;
+        Ok(reward)
+    }
+
+    /// Price a buyer offering `offered` is charged for `tier` at `now`. In a
+    /// Dutch auction the offer is a maximum: the current auction price is
+    /// charged if it's no higher. Otherwise the offer is passed through for
+    /// `record_sale` to check.
+    pub fn clearing_price(&self, tier: TicketTier, offered: u64, now: i64) -> Result<u64> {
+        if !matches!(self.pricing_mode, PricingMode::DutchAuction { .. }) {
+            return Ok(offered);
+        }
+        let price = self.current_price(tier, now)?;
+        require!(offered >= price, TicketError::BelowAuctionPrice);
+        Ok(price)
+    }
+
+    /// Count one more ticket against overall and tier capacity, failing once
+    /// either is sold out or the price doesn't match `current_price`.
+    pub fn record_sale(&mut self, tier: TicketTier, price: u64, now: i64) -> Result<()> {
+        require!(
+            price == self.current_price(tier, now)?,
+            TicketError::PriceMismatch
+        );
+        self.reserve_seat(tier)
+    }
+
+    /// Price of `tier` at `now`: the tier price, less `early_bird_bps` for
+    /// purchases strictly before `early_bird_deadline`. At the deadline
+    /// itself the full price applies. On a `LinearCurve` or in a
+    /// `DutchAuction` that price applies to every tier and the early bird
+    /// discount doesn't.
+    pub fn current_price(&self, tier: TicketTier, now: i64) -> Result<u64> {
+        match self.pricing_mode {
+            PricingMode::Fixed => {}
+            PricingMode::LinearCurve { base, step } => {
+                return math::add(base, math::mul(step, self.tickets_sold as u64)?);
+            }
+            PricingMode::DutchAuction {
+                start_price,
+                floor_price,
+                start_time,
+                decay_per_sec,
+            } => {
+                let elapsed = now.saturating_sub(start_time).max(0) as u64;
+                let decay = decay_per_sec.saturating_mul(elapsed);
+                return Ok(start_price.saturating_sub(decay).max(floor_price));
+            }
+        }
+        let list_price = self.tier_prices[tier.index()];
+        if now < self.early_bird_deadline {
+            math::sub(list_price, math::fee_of(list_price, self.early_bird_bps)?)
+        } else {
+            Ok(list_price)
+        }
+    }
+
+    /// Count one more ticket against overall and tier capacity without a
+    /// price check, for tickets the organizer issues directly. A tier being
+    /// allocated by lottery is only reachable through `take_seat`.
+    pub fn reserve_seat(&mut self, tier: TicketTier) -> Result<()> {
+        require!(
+            !matches!(&self.lottery, Some(lottery) if lottery.tier == tier),
+            TicketError::LotteryTier
+        );
+        self.take_seat(tier)
+    }
+
+    /// Capacity bookkeeping shared by every way of issuing a ticket.
+    pub fn take_seat(&mut self, tier: TicketTier) -> Result<()> {
+        require!(
+            self.status != EventStatus::Cancelled,
+            TicketError::EventCancelled
+        );
+        require!(
+            matches!(self.status, EventStatus::Scheduled | EventStatus::Live),
+            TicketError::InvalidEventStatus
+        );
+        let i = tier.index();
+        require!(self.tickets_sold < self.capacity, TicketError::SoldOut);
+        require!(
+            self.tier_sold[i] < self.tier_capacities[i],
+            TicketError::SoldOut
+        );
+        self.tickets_sold += 1;
+        self.tier_sold[i] += 1;
+        if self.min_tickets > 0 && self.tickets_sold >= self.min_tickets {
+            self.confirmed = true;
+        }
+        Ok(())
+    }
+
+    /// Move one sold ticket from tier `from` to tier `to`, failing with
+    /// `SoldOut` if `to` is full. `tickets_sold` is unchanged.
+    pub fn move_seat(&mut self, from: TicketTier, to: TicketTier) -> Result<()> {
+        require!(
+            !matches!(&self.lottery, Some(lottery) if lottery.tier == to),
+            TicketError::LotteryTier
+        );
+        require!(
+            matches!(self.status, EventStatus::Scheduled | EventStatus::Live),
+            TicketError::InvalidEventStatus
+        );
+        let (from, to) = (from.index(), to.index());
+        require!(
+            self.tier_sold[to] < self.tier_capacities[to],
+            TicketError::SoldOut
+        );
+        self.tier_sold[from] = self.tier_sold[from]
+            .checked_sub(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        self.tier_sold[to] += 1;
+        Ok(())
+    }
+
+    /// Split a resale at `price` after the platform `fee` into the organizer
+    /// royalty, the original-buyer royalty and the seller's share. The
+    /// original buyer gets nothing when unrecorded or when they're the seller.
+    pub fn resale_shares(&self, price: u64, fee: u64, ticket: &Ticket) -> Result<(u64, u64, u64)> {
+        let royalty = math::fee_of(price, self.royalty_bps)?;
+        let original_royalty = if ticket.original_buyer == Pubkey::default()
+            || ticket.original_buyer == ticket.owner
+        {
+            0
+        } else {
+            math::fee_of(price, self.original_buyer_royalty_bps)?
+        };
+        let royalties = math::add(royalty, original_royalty)?;
+        let seller_share = math::sub(price, math::add(fee, royalties)?)?;
+        Ok((royalty, original_royalty, seller_share))
+    }
+
+    /// Per-ticket price for a batch of `count` tickets at `list_price`, less
+    /// the largest group discount the batch qualifies for.
+    pub fn group_price(&self, list_price: u64, count: usize) -> Result<u64> {
+        let discount_bps = self
+            .group_discounts
+            .iter()
+            .rev()
+            .find(|tier| count >= tier.min_qty as usize)
+            .map_or(0, |tier| tier.discount_bps);
+        math::sub(list_price, math::fee_of(list_price, discount_bps)?)
+    }
+
+    /// Add a sale's `amount` to `gross_revenue`, failing with
+    /// `RevenueCapReached` if that would pass `revenue_cap`. Reaching the
+    /// cap exactly is allowed.
+    pub fn record_revenue(&mut self, amount: u64) -> Result<()> {
+        let gross_revenue = math::add(self.gross_revenue, amount)?;
+        require!(
+            self.revenue_cap == 0 || gross_revenue <= self.revenue_cap,
+            TicketError::RevenueCapReached
+        );
+        self.gross_revenue = gross_revenue;
+        Ok(())
+    }
+
+    /// Index of the festival day `now` falls on, counting whole days from
+    /// `event_date` and clamped to `0..days`.
+    pub fn day_of(&self, now: i64) -> u8 {
+        let elapsed = now.saturating_sub(self.event_date).max(0) / (24 * 60 * 60);
+        elapsed.min(self.days.max(1) as i64 - 1) as u8
+    }
+
+    /// Fail unless `price` is within the resale bounds: at most
+    /// `max_resale_price`, and while royalties are enforced on transfer, at
+    /// least `min_resale_price`.
+    pub fn check_resale_price(&self, price: u64) -> Result<()> {
+        require!(
+            price <= self.max_resale_price,
+            TicketError::ResalePriceTooHigh
+        );
+        require!(
+            !self.enforce_royalty_on_transfer || price >= self.min_resale_price,
+            TicketError::ResalePriceTooLow
+        );
+        Ok(())
+    }
+
+    /// Whether `transfer_ticket` may hand a ticket to `recipient` for free.
+    pub fn allows_free_transfer_to(&self, recipient: &Pubkey) -> bool {
+        !self.enforce_royalty_on_transfer
+            || *recipient == self.organizer
+            || self.free_transfer_recipients.contains(recipient)
+    }
+
+    /// Fail with `TermsMismatch` unless `accepted` is the event's current
+    /// `terms_hash`. Events without terms accept anything.
+    pub fn check_terms(&self, accepted: &[u8; 32]) -> Result<()> {
+        require!(
+            self.terms_hash == [0u8; 32] || *accepted == self.terms_hash,
+            TicketError::TermsMismatch
+        );
+        Ok(())
+    }
+
+    /// On a gated event, fail with `AttestationRequired` unless `attestation`
+    /// (already checked to be the buyer's, from `attester`, for
+    /// `required_attestation`) is positive and unexpired at `now`.
+    pub fn check_attestation(&self, attestation: Option<&Attestation>, now: i64) -> Result<()> {
+        if self.required_attestation == 0 {
+            return Ok(());
+        }
+        require!(
+            attestation.is_some_and(|attestation| {
+                attestation.verified && now < attestation.expires_at
+            }),
+            TicketError::AttestationRequired
+        );
+        Ok(())
+    }
+
+    /// Whether waitlist entries left at `now` can no longer be promoted:
+    /// the event has started or been cancelled.
+    pub fn waitlist_closed(&self, now: i64) -> bool {
+        self.status == EventStatus::Cancelled || now >= self.event_date
+    }
+
+    /// Whether no ticket of `tier` can currently be issued.
+    pub fn is_sold_out(&self, tier: TicketTier) -> bool {
+        let i = tier.index();
+        self.tickets_sold >= self.capacity || self.tier_sold[i] >= self.tier_capacities[i]
+    }
+
+    /// Undo `reserve_seat`, returning one ticket of `tier` to sale.
+    pub fn release_seat(&mut self, tier: TicketTier) -> Result<()> {
+        self.release_seats(tier, 1)
+    }
+
+    /// Put `count` seats of `tier` back on sale, e.g. for a merged ticket.
+    pub fn release_seats(&mut self, tier: TicketTier, count: u32) -> Result<()> {
+        let i = tier.index();
+        self.tickets_sold = self
+            .tickets_sold
+            .checked_sub(count)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        self.tier_sold[i] = self.tier_sold[i]
+            .checked_sub(count)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Move up to `amount` of escrow from `locked` to `withdrawable`, once the
+    /// ticket it backs has been redeemed.
+    pub fn unlock(&mut self, amount: u64) -> Result<()> {
+        let amount = amount.min(self.locked);
+        self.locked -= amount;
+        self.withdrawable = math::add(self.withdrawable, amount)?;
+        Ok(())
+    }
+
+    /// Escrow the organizer may withdraw at `now`: unlocked proceeds, plus
+    /// everything still locked once the event date has passed.
+    pub fn withdrawable_at(&self, now: i64) -> Result<u64> {
+        if now > self.event_date {
+            math::add(self.withdrawable, self.locked)
+        } else {
+            Ok(self.withdrawable)
+        }
+    }
+
+    /// Change `status`, failing with `InvalidEventStatus` unless the move is
+    /// allowed by `EventStatus::can_transition_to`.
+    pub fn transition(&mut self, next: EventStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next),
+            TicketError::InvalidEventStatus
+        );
+        let previous = self.status;
+        self.status = next;
+
+        emit!(EventStatusChanged {
+            event_id: self.event_id.clone(),
+            from: previous,
+            to: next,
+        });
+        Ok(())
+    }
+
+    /// Share of the refundable amount returned when cancelling at `now`,
+    /// per `refund_policy`.
+    pub fn refund_bps(&self, now: i64) -> u16 {
+        if self.refund_policy.is_empty() {
+            return MAX_BPS;
+        }
+        let time_left = self.event_date.saturating_sub(now);
+        self.refund_policy
+            .iter()
+            .find(|tier| time_left > tier.seconds_before)
+            .map_or(0, |tier| tier.refund_bps)
+    }
+
+    /// Whether `signer` may redeem a ticket issued under `venue_authority`:
+    /// the venue authority itself or any listed redemption delegate.
+    pub fn is_redeemer(&self, venue_authority: &Pubkey, signer: &Pubkey) -> bool {
+        signer == venue_authority || self.redemption_delegates.contains(signer)
+    }
+
+    /// Fail with `InsufficientPermission` unless `signer` is the organizer
+    /// or a co-organizer holding every bit in `permission`.
+    pub fn require_permission(&self, signer: &Pubkey, permission: u8) -> Result<()> {
+        let allowed = *signer == self.organizer
+            || self
+                .co_organizers
+                .iter()
+                .any(|co| co.key == *signer && co.permissions & permission == permission);
+        require!(allowed, TicketError::InsufficientPermission);
+        Ok(())
+    }
+
+    /// Fail unless `now` falls inside the event's sales window.
+    pub fn check_sales_window(&self, now: i64) -> Result<()> {
+        require!(now >= self.sales_start, TicketError::SalesNotOpen);
+        require!(
+            self.sales_end == 0 || now <= self.sales_end,
+            TicketError::SalesClosed
+        );
+        Ok(())
+    }
+
+    /// Last second tickets can be scanned: the redemption window's end, or
+    /// `event_date + late_entry_grace` if that is later.
+    pub fn redemption_end(&self) -> i64 {
+        self.redeem_window_end
+            .max(self.event_date.saturating_add(self.late_entry_grace))
+    }
+
+    /// Fail unless `now` falls inside the event's redemption window,
+    /// including any late-entry grace.
+    pub fn check_redemption_window(&self, now: i64) -> Result<()> {
+        require!(
+            now >= self.redeem_window_start && now <= self.redemption_end(),
+            TicketError::OutsideRedemptionWindow
+        );
+        Ok(())
+    }
+}
+
+/// Running totals for one event, at `[b"stats", event]`. Zero-copy, so a
+/// purchase updates a few fields in place instead of (de)serializing a
+/// second large account. `tickets_sold` and `revenue` are gross of refunds.
+#[account(zero_copy)]
+pub struct EventStats {
+    pub event: Pubkey,          // 32 bytes
+    pub revenue: u64,           // 8 bytes (lamports, gross)
+    pub tickets_sold: u32,      // 4 bytes
+    pub redeemed: u32,          // 4 bytes
+    pub refunded: u32,          // 4 bytes
+    pub bump: u8,               // 1 byte
+    pub _padding: [u8; 3],      // 3 bytes (keeps the layout free of implicit padding)
+}
+
+impl EventStats {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + std::mem::size_of::<EventStats>();
+
+    pub fn record_sale(&mut self, price: u64) -> Result<()> {
+        self.tickets_sold = self
+            .tickets_sold
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        self.revenue = math::add(self.revenue, price)?;
+        Ok(())
+    }
+
+    pub fn record_redemption(&mut self) -> Result<()> {
+        self.redeemed = self
+            .redeemed
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_refund(&mut self) -> Result<()> {
+        self.refunded = self
+            .refunded
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// A resale offer for one ticket, at `[b"listing", ticket]`.
+#[account]
+pub struct Listing {
+    pub ticket: Pubkey,         // 32 bytes
+    pub seller: Pubkey,         // 32 bytes
+    pub price: u64,             // 8 bytes
+    pub expiry: i64,            // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl Listing {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 1;
+}
+
+/// A bid on one ticket, at `[b"offer", ticket, bidder]`. The offered
+/// lamports are held in this account on top of its rent.
+#[account]
+pub struct Offer {
+    pub ticket: Pubkey,         // 32 bytes
+    pub bidder: Pubkey,         // 32 bytes
+    pub amount: u64,            // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl Offer {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+/// A place in an event's waitlist, at `[b"waitlist", event, sequence]`.
+/// Holds the deposit on top of its rent until promotion or leaving.
+#[account]
+pub struct WaitlistEntry {
+    pub event: Pubkey,          // 32 bytes
+    pub entrant: Pubkey,        // 32 bytes
+    pub sequence: u64,          // 8 bytes (join order, from event.waitlist_next)
+    pub tier: TicketTier,       // 1 byte
+    pub deposit: u64,           // 8 bytes
+    pub event_name: FixedStr<128>, // 1 + 128 bytes
+    pub attendee_name: FixedStr<64>, // 1 + 64 bytes
+    pub metadata_uri: String,   // 4 + 200 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl WaitlistEntry {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 1 + 8
+        + (1 + Ticket::MAX_EVENT_NAME_LEN)
+        + (1 + Ticket::MAX_ATTENDEE_NAME_LEN)
+        + (4 + Ticket::MAX_METADATA_URI_LEN)
+        + 1;
+}
+
+/// A pass admitting `holder` once to each of several events, at
+/// `[b"pass", organizer, seed_hash(pass_id), holder]`. Events are stored by
+/// `seed_hash(event_id)`, the same hash their Event PDAs are seeded with.
+#[account]
+pub struct SeasonPass {
+    pub organizer: Pubkey,      // 32 bytes
+    pub holder: Pubkey,         // 32 bytes
+    pub pass_id: FixedStr<32>,  // 1 + 32 bytes
+    pub events: Vec<[u8; 32]>,  // 4 + 32 * 16 bytes
+    pub redeemed_events: Vec<[u8; 32]>, // 4 + 32 * 16 bytes
+    pub created_at: i64,        // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl SeasonPass {
+    pub const MAX_PASS_ID_LEN: usize = 32;
+    pub const MAX_EVENTS: usize = 16;
+
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32
+        + (1 + Self::MAX_PASS_ID_LEN)
+        + (4 + 32 * Self::MAX_EVENTS) * 2
+        + 8 + 1;
+}
+
+/// A venue-signed pass for offline scanning, at `[b"gate_pass", ticket]`.
+/// Off-chain, scanners verify `signature` over
+/// `gate_pass_message(ticket, holder)` against `signer`; on-chain,
+/// `settle_gate_passes` records the earliest scan and counts the rest.
+#[account]
+pub struct GatePass {
+    pub ticket: Pubkey,         // 32 bytes
+    pub holder: Pubkey,         // 32 bytes (ticket owner the pass admits)
+    pub signer: Pubkey,         // 32 bytes (venue authority or delegate that signed)
+    pub signature: [u8; 64],    // 64 bytes (Ed25519 signature over the pass message)
+    pub issued_at: i64,         // 8 bytes
+    pub scanned_at: i64,        // 8 bytes (earliest settled scan; 0 = not settled)
+    pub scanner: Pubkey,        // 32 bytes (who made that scan; default = online redemption)
+    pub conflicts: u8,          // 1 byte (later scans of the same ticket)
+    pub bump: u8,               // 1 byte
+}
+
+impl GatePass {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 64 + 8 + 8 + 32 + 1 + 1;
+}
+
+/// One offline scan reported to `settle_gate_passes`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OfflineScan {
+    pub scanned_at: i64,
+    /// Venue authority or redemption delegate the scanner app signs in as
+    pub scanner: Pubkey,
+}
+
+/// Running totals across one organizer's events, at
+/// `[b"organizer", organizer]`, so a dashboard doesn't have to scan every
+/// Event. Revenue is in lamports, net of refunds. Refunds saturate at zero,
+/// since they may be for sales made before the profile existed.
+#[account]
+pub struct OrganizerProfile {
+    pub organizer: Pubkey,      // 32 bytes
+    pub total_events: u32,      // 4 bytes
+    pub events_active: u32,     // 4 bytes (not yet Ended or Cancelled)
+    pub total_tickets_sold: u64, // 8 bytes
+    pub total_revenue: u64,     // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl OrganizerProfile {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 4 + 4 + 8 + 8 + 1;
+
+    pub fn record_event_created(&mut self) -> Result<()> {
+        self.total_events = self
+            .total_events
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        self.events_active = self
+            .events_active
+            .checked_add(1)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_event_closed(&mut self) -> Result<()> {
+        self.events_active = self.events_active.saturating_sub(1);
+        Ok(())
+    }
+
+    pub fn record_sale(&mut self, price: u64) -> Result<()> {
+        self.total_tickets_sold = math::add(self.total_tickets_sold, 1)?;
+        self.total_revenue = math::add(self.total_revenue, price)?;
+        Ok(())
+    }
+
+    pub fn record_refund(&mut self, price: u64) -> Result<()> {
+        self.total_tickets_sold = self.total_tickets_sold.saturating_sub(1);
+        self.total_revenue = self.total_revenue.saturating_sub(price);
+        Ok(())
+    }
+}
+
+/// Referral rewards owed to `referrer`, at `[b"referral", referrer]`. The
+/// account holds the unwithdrawn rewards as lamports on top of its rent.
+#[account]
+pub struct ReferralRewards {
+    pub referrer: Pubkey,       // 32 bytes
+    pub earned: u64,            // 8 bytes (lifetime)
+    pub withdrawn: u64,         // 8 bytes (lifetime)
+    pub bump: u8,               // 1 byte
+}
+
+impl ReferralRewards {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+/// Prepaid credits for `owner`, at `[b"credits", owner]`. The account holds
+/// `balance` lamports on top of its rent.
+#[account]
+pub struct CreditBalance {
+    pub owner: Pubkey,          // 32 bytes
+    pub balance: u64,           // 8 bytes (lamports)
+    pub bump: u8,               // 1 byte
+}
+
+impl CreditBalance {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 8 + 1;
+
+    /// Take `amount` off the balance, failing if it doesn't cover it.
+    pub fn debit(&mut self, amount: u64) -> Result<()> {
+        self.balance = self
+            .balance
+            .checked_sub(amount)
+            .ok_or(TicketError::InsufficientCredits)?;
+        Ok(())
+    }
+}
+
+/// An attester's answer to whether `wallet` meets `requirement`, at
+/// `[b"attestation", attester, wallet, [requirement]]`. Holds only a yes/no
+/// and an expiry — never the underlying personal data.
+#[account]
+pub struct Attestation {
+    pub attester: Pubkey,       // 32 bytes
+    pub wallet: Pubkey,         // 32 bytes
+    pub requirement: u8,        // 1 byte (e.g. AGE_21_PLUS)
+    pub verified: bool,         // 1 byte
+    pub expires_at: i64,        // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl Attestation {
+    pub const AGE_18_PLUS: u8 = 1;
+    pub const AGE_21_PLUS: u8 = 2;
+    pub const IDENTITY_VERIFIED: u8 = 3;
+
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 1 + 1 + 8 + 1;
+}
+
+/// An attendee's score for an event, at `[b"rating", ticket]`, so each
+/// ticket rates once.
+#[account]
+pub struct Rating {
+    pub ticket: Pubkey,         // 32 bytes
+    pub rater: Pubkey,          // 32 bytes
+    pub score: u8,              // 1 byte (1–5)
+    pub rated_at: i64,          // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl Rating {
+    pub const MIN_SCORE: u8 = 1;
+    pub const MAX_SCORE: u8 = 5;
+
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 1 + 8 + 1;
+}
+
+/// A ticket holder's dispute awaiting the arbiter, at `[b"dispute", ticket]`.
+#[account]
+pub struct Dispute {
+    pub ticket: Pubkey,         // 32 bytes
+    pub opener: Pubkey,         // 32 bytes
+    pub reason_code: u8,        // 1 byte
+    pub status: DisputeStatus,  // 1 byte
+    pub resolution_code: u8,    // 1 byte
+    pub opened_at: i64,         // 8 bytes
+    pub resolved_at: i64,       // 8 bytes (0 = open)
+    pub bump: u8,               // 1 byte
+}
+
+impl Dispute {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 1 + 1 + 1 + 8 + 8 + 1;
+}
+
+/// One entrant's registration in an event lottery, at
+/// `[b"entry", event, entrant]`. Holds the deposit on top of its rent.
+#[account]
+pub struct Entry {
+    pub event: Pubkey,          // 32 bytes
+    pub entrant: Pubkey,        // 32 bytes
+    pub index: u32,             // 4 bytes (registration order)
+    pub deposit: u64,           // 8 bytes
+    pub bump: u8,               // 1 byte
+}
 
-        msg!("Ticket created for {} — Status: Active", ticket.attendee_name);
-        Ok(())
-    }
+impl Entry {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 4 + 8 + 1;
+}
 
-    /// Redeem a ticket at the venue entrance.
-    /// Only callable by the venue authority.
-    /// Marks ticket as Redeemed, preventing reuse.
-    pub fn redeem_ticket(ctx: Context<RedeemTicket>) -> Result<()> {
-        let ticket = &mut ctx.accounts.ticket;
+/// One city on a tour, at `[b"stop", seed_hash(event_id), seed_hash(venue)]`,
+/// with inventory kept separate from the tour's other stops.
+#[account]
+pub struct VenueStop {
+    pub event: Pubkey,          // 32 bytes
+    pub venue: String,          // 4 + 64 bytes
+    pub capacity: u32,          // 4 bytes
+    pub tickets_sold: u32,      // 4 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl VenueStop {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + (4 + Ticket::MAX_VENUE_LEN) + 4 + 4 + 1;
 
+    /// Count one more ticket against this stop, failing once it's sold out.
+    pub fn reserve_seat(&mut self) -> Result<()> {
         require!(
-            ticket.ticket_status == TicketStatus::Active,
-            TicketError::TicketNotActive
+            self.tickets_sold < self.capacity,
+            TicketError::VenueStopSoldOut
         );
+        self.tickets_sold += 1;
+        Ok(())
+    }
+}
 
-        ticket.ticket_status = TicketStatus::Redeemed;
+/// A wallet banned from an event, at
+/// `[b"blacklist", seed_hash(event_id), wallet]`.
+#[account]
+pub struct BlacklistEntry {
+    pub event: Pubkey,          // 32 bytes
+    pub wallet: Pubkey,         // 32 bytes
+    pub bump: u8,               // 1 byte
+}
 
-        msg!(
-            "Ticket redeemed for {} at {}",
-            ticket.attendee_name,
-            ticket.venue
+impl BlacklistEntry {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 1;
+}
+
+/// A discount code, at `[b"promo", seed_hash(event_id), code_hash]`.
+#[account]
+pub struct PromoCode {
+    pub event: Pubkey,          // 32 bytes
+    pub code_hash: [u8; 32],    // 32 bytes (SHA-256 of the code)
+    pub discount_bps: u16,      // 2 bytes
+    pub max_uses: u32,          // 4 bytes
+    pub uses: u32,              // 4 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl PromoCode {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 32 + 32 + 2 + 4 + 4 + 1;
+}
+
+/// Per-buyer, per-event purchase counter used to enforce `max_per_buyer`.
+#[account]
+pub struct BuyerAllocation {
+    pub count: u8,              // 1 byte
+    pub bump: u8,               // 1 byte
+}
+
+impl BuyerAllocation {
+    // Discriminator (8) + all fields
+    pub const SIZE: usize = 8 + 1 + 1;
+
+    /// Count one more ticket for this buyer, failing past the event's limit.
+    pub fn record_purchase(&mut self, max_per_buyer: u8, bump: u8) -> Result<()> {
+        self.count = self
+            .count
+            .checked_add(1)
+            .ok_or(TicketError::PurchaseLimitExceeded)?;
+        require!(
+            self.count <= max_per_buyer,
+            TicketError::PurchaseLimitExceeded
         );
+        self.bump = bump;
         Ok(())
     }
+}
 
-    /// Get ticket information (view-only helper).
-    /// In practice, ticket data is read directly from the PDA account.
-    pub fn get_ticket_info(ctx: Context<GetTicketInfo>) -> Result<()> {
-        let ticket = &ctx.accounts.ticket;
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum TicketStatus {
+    Active,
+    Redeemed,
+    Cancelled,
+    NoShow,
+    /// Held by the box office until `reserved_until`, not yet paid for.
+    Reserved,
+}
 
-        msg!("=== Ticket Info ===");
-        msg!("Event: {}", ticket.event_name);
-        msg!("Venue: {}", ticket.venue);
-        msg!("Attendee: {}", ticket.attendee_name);
-        msg!("Date: {}", ticket.event_date);
-        msg!("Price: {} lamports", ticket.price_paid);
-        msg!("Status: {:?}", ticket.ticket_status);
-        msg!("Owner: {}", ticket.owner);
-        msg!("cNFT: {}", ticket.cnft_asset_id);
+/// Why a ticket was cancelled, recorded on the ticket and in
+/// `TicketCancelled`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CancelReason {
+    BuyerRequested,
+    EventCancelled,
+    Fraud,
+    Duplicate,
+    /// Anything else, including refunds ordered by dispute resolution.
+    Other,
+}
 
-        Ok(())
+/// Where a dispute stands; everything but `Open` is final.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeStatus {
+    Open,
+    Refunded,
+    Reinstated,
+    Rejected,
+}
+
+/// The arbiter's ruling on a dispute.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeResolution {
+    Refund,
+    Reinstate,
+    Reject,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventStatus {
+    Scheduled,
+    Live,
+    Ended,
+    Cancelled,
+}
+
+impl EventStatus {
+    /// Lifecycle rules: Scheduled -> Live -> Ended, with Cancelled reachable
+    /// from either open state. Ended and Cancelled are terminal.
+    pub fn can_transition_to(self, next: EventStatus) -> bool {
+        matches!(
+            (self, next),
+            (EventStatus::Scheduled, EventStatus::Live)
+                | (EventStatus::Live, EventStatus::Ended)
+                | (EventStatus::Scheduled | EventStatus::Live, EventStatus::Cancelled)
+        )
+    }
+
+    pub fn is_terminal(self) -> bool {
+        matches!(self, EventStatus::Ended | EventStatus::Cancelled)
     }
 }
 
-// --- Account Structures ---
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TicketTier {
+    GeneralAdmission,
+    Vip,
+    Backstage,
+}
 
-#[derive(Accounts)]
-#[instruction(event_id: String, event_name: String, event_date: i64, venue: String, attendee_name: String)]
-pub struct PurchaseTicket<'info> {
-    #[account(mut)]
-    pub buyer: Signer<'info>,
+impl TicketTier {
+    /// Index into the Event's per-tier arrays.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
 
-    /// CHECK: Venue wallet receives payment. Not validated beyond being writable.
-    #[account(mut)]
-    pub venue: AccountInfo<'info>,
+/// How an event prices its tickets (see `Event::current_price`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PricingMode {
+    /// Per-tier prices, with the optional early bird discount.
+    Fixed,
+    /// `base + step * tickets_sold` lamports for the next ticket.
+    LinearCurve { base: u64, step: u64 },
+    /// `start_price - decay_per_sec * (now - start_time)` lamports, never
+    /// below `floor_price`.
+    DutchAuction {
+        start_price: u64,
+        floor_price: u64,
+        start_time: i64,
+        decay_per_sec: u64,
+    },
+}
 
-    #[account(
-        init,
-        payer = buyer,
-        space = Ticket::SIZE,
-        seeds = [
-            b"ticket",
-            event_id.as_bytes(),
-            attendee_name.as_bytes(),
-            buyer.key().as_ref(),
-        ],
-        bump,
-    )]
-    pub ticket: Account<'info, Ticket>,
+impl PricingMode {
+    /// Serialized size of the largest variant.
+    pub const SIZE: usize = 1 + 8 + 8 + 8 + 8;
+}
 
-    pub system_program: Program<'info, System>,
+/// What an event's tickets are paid in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Currency {
+    Sol,
+    Usdc,
+    CustomSpl(Pubkey),
 }
 
-#[derive(Accounts)]
-pub struct RedeemTicket<'info> {
-    /// Venue authority — only the venue can redeem tickets
-    pub venue_authority: Signer<'info>,
+impl Currency {
+    /// Serialized size of the largest variant.
+    pub const SIZE: usize = 1 + 32;
 
-    #[account(
-        mut,
-        constraint = ticket.ticket_status == TicketStatus::Active @ TicketError::TicketNotActive,
-    )]
-    pub ticket: Account<'info, Ticket>,
+    /// Mint tickets are paid in, recorded as the ticket's `payment_mint`;
+    /// the default pubkey for SOL.
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            Currency::Sol => Pubkey::default(),
+            Currency::Usdc => USDC_MINT,
+            Currency::CustomSpl(mint) => *mint,
+        }
+    }
 }
 
-#[derive(Accounts)]
-pub struct GetTicketInfo<'info> {
-    pub ticket: Account<'info, Ticket>,
+// --- Events ---
+
+#[event]
+pub struct LotteryDrawn {
+    pub event_id: String,
+    pub seed: [u8; 32],
+    pub entrants: u32,
+    pub winners: u32,
 }
 
-// --- Data Structures ---
+#[event]
+pub struct DisputeResolved {
+    pub ticket: Pubkey,
+    pub status: DisputeStatus,
+    pub resolution_code: u8,
+}
 
-#[account]
-pub struct Ticket {
-    pub event_id: String,       // 4 + 64 bytes
-    pub event_name: String,     // 4 + 128 bytes
-    pub event_date: i64,        // 8 bytes
-    pub venue: String,          // 4 + 64 bytes
-    pub attendee_name: String,  // 4 + 64 bytes
-    pub price_paid: u64,        // 8 bytes
-    pub ticket_status: TicketStatus, // 1 byte
-    pub cnft_asset_id: Pubkey,  // 32 bytes
-    pub owner: Pubkey,          // 32 bytes
-    pub created_at: i64,        // 8 bytes
-    pub bump: u8,               // 1 byte
+#[event]
+pub struct TicketFrozen {
+    pub ticket: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-impl Ticket {
-    // Discriminator (8) + all fields
-    pub const SIZE: usize = 8 + (4 + 64) + (4 + 128) + 8 + (4 + 64) + (4 + 64) + 8 + 1 + 32 + 32 + 8 + 1;
+#[event]
+pub struct TicketUnfrozen {
+    pub ticket: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
-pub enum TicketStatus {
-    Active,
-    Redeemed,
-    Cancelled,
+#[event]
+pub struct PassRedeemed {
+    pub pass: Pubkey,
+    pub event_id: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventStatusChanged {
+    pub event_id: String,
+    pub from: EventStatus,
+    pub to: EventStatus,
+}
+
+#[event]
+pub struct TicketPurchased {
+    pub ticket: Pubkey,
+    pub owner: Pubkey,
+    pub event_id: String,
+    pub price_paid: u64,
+}
+
+#[event]
+pub struct TicketRedeemed {
+    pub ticket: Pubkey,
+    pub venue: String,
+    pub timestamp: i64,
+    pub scan_count: u16,
+}
+
+#[event]
+pub struct RedemptionSkipped {
+    pub ticket: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GateScanConflict {
+    pub ticket: Pubkey,
+    pub kept_scanned_at: i64,
+    pub kept_scanner: Pubkey,
+    pub flagged_scanned_at: i64,
+    pub flagged_scanner: Pubkey,
+}
+
+#[event]
+pub struct TicketCancelled {
+    pub ticket: Pubkey,
+    pub owner: Pubkey,
+    pub refund_amount: u64,
+    pub reason: CancelReason,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DepositRefunded {
+    /// Closed `WaitlistEntry` or lottery `Entry`
+    pub entry: Pubkey,
+    pub entrant: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PartialRefundIssued {
+    pub ticket: Pubkey,
+    pub owner: Pubkey,
+    pub refund_amount: u64,
+    pub refunded_total: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventRescheduled {
+    pub event_id: String,
+    pub old_date: i64,
+    pub new_date: i64,
+}
+
+#[event]
+pub struct TierChanged {
+    pub ticket: Pubkey,
+    pub from: TicketTier,
+    pub to: TicketTier,
+    /// Lamports paid for an upgrade, or refunded for a downgrade
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventMetadataUpdated {
+    pub event: Pubkey,
+    pub old_event_name: String,
+    pub new_event_name: String,
+    pub old_venue: String,
+    pub new_venue: String,
+    pub old_metadata_uri: String,
+    pub new_metadata_uri: String,
+    /// Made after tickets were sold, with `reason`
+    pub forced: bool,
+    pub reason: String,
+    pub timestamp: i64,
 }
 
 // --- Errors ---
@@ -181,4 +9381,815 @@ pub enum TicketError {
     TicketNotActive,
     #[msg("Unauthorized: only venue can redeem tickets")]
     UnauthorizedRedemption,
+    #[msg("Unauthorized: only the ticket owner can transfer it")]
+    UnauthorizedTransfer,
+    #[msg("Ticket cannot be transferred (soulbound, redeemed or cancelled)")]
+    TicketNotTransferable,
+    #[msg("Token account mint does not match the payment mint")]
+    MintMismatch,
+    #[msg("Buyer token account is not owned by the buyer")]
+    InvalidTokenOwner,
+    #[msg("Account does not match the ticket owner")]
+    OwnerMismatch,
+    #[msg("Unauthorized: only venue can cancel tickets")]
+    UnauthorizedCancellation,
+    #[msg("Field exceeds its maximum length")]
+    FieldTooLong,
+    #[msg("Ticket is on hold, or still active and the event has not passed")]
+    CannotCloseActiveTicket,
+    #[msg("Event capacity must be non-zero and equal the sum of tier capacities")]
+    InvalidCapacity,
+    #[msg("Event is sold out")]
+    SoldOut,
+    #[msg("Per-buyer ticket limit must be greater than zero")]
+    InvalidPurchaseLimit,
+    #[msg("Buyer has reached the ticket limit for this event")]
+    PurchaseLimitExceeded,
+    #[msg("Platform fee cannot exceed 10% (1000 bps)")]
+    InvalidFee,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Redemption window must start before it ends")]
+    InvalidRedemptionWindow,
+    #[msg("Ticket can only be redeemed within the event's redemption window")]
+    OutsideRedemptionWindow,
+    #[msg("Price does not match the configured price for this tier")]
+    PriceMismatch,
+    #[msg("Seat must not be empty")]
+    InvalidSeat,
+    #[msg("Unauthorized: only the event organizer can do this")]
+    UnauthorizedOrganizer,
+    #[msg("Royalties cannot exceed 100% (10000 bps) combined")]
+    InvalidRoyalty,
+    #[msg("Resale price exceeds the event's maximum")]
+    ResalePriceTooHigh,
+    #[msg("Venue does not match the event's venue authority")]
+    VenueMismatch,
+    #[msg("Event has not taken place yet")]
+    EventNotOver,
+    #[msg("Escrow balance is insufficient for this payout")]
+    InsufficientEscrow,
+    #[msg("Event has been cancelled")]
+    EventCancelled,
+    #[msg("Refunds are only available for cancelled events")]
+    EventNotCancelled,
+    #[msg("Metadata URI must start with https://, ipfs:// or ar://")]
+    InvalidUri,
+    #[msg("cNFT accounts are required to burn the ticket NFT")]
+    MissingCnftAccounts,
+    #[msg("cNFT leaf does not match the ticket's asset id")]
+    CnftMismatch,
+    #[msg("Missing or malformed Ed25519 signature instruction")]
+    InvalidSignature,
+    #[msg("Signature is for a stale redemption nonce")]
+    NonceMismatch,
+    #[msg("Buyer is not on the presale allowlist")]
+    NotOnAllowlist,
+    #[msg("Only allowlisted presale purchases are open")]
+    PresaleOnly,
+    #[msg("Presale is not open")]
+    PresaleNotOpen,
+    #[msg("Sales window must end after it starts")]
+    InvalidSalesWindow,
+    #[msg("Ticket sales have not opened yet")]
+    SalesNotOpen,
+    #[msg("Ticket sales have closed")]
+    SalesClosed,
+    #[msg("Only the program admin can change config")]
+    UnauthorizedAdmin,
+    #[msg("Fee destination must be the configured treasury")]
+    TreasuryMismatch,
+    #[msg("Batch must contain between 1 and 10 tickets")]
+    BatchTooLarge,
+    #[msg("Ticket accounts don't match the attendee list")]
+    TicketAccountMismatch,
+    #[msg("Ticket account already exists")]
+    TicketAlreadyExists,
+    #[msg("Ticket has already been scanned and re-entry is not allowed")]
+    ReentryNotAllowed,
+    #[msg("Too many redemption delegates (max 16)")]
+    TooManyDelegates,
+    #[msg("Delegate is already authorized")]
+    DelegateAlreadyAdded,
+    #[msg("Delegate not found")]
+    DelegateNotFound,
+    #[msg("Invalid claim code")]
+    InvalidClaimCode,
+    #[msg("Ticket is not waiting to be claimed")]
+    TicketNotClaimable,
+    #[msg("Claim deadline has passed")]
+    ClaimExpired,
+    #[msg("Claim deadline has not passed yet")]
+    ClaimNotExpired,
+    #[msg("Event is not in a status that allows this")]
+    InvalidEventStatus,
+    #[msg("Listing has expired")]
+    ListingExpired,
+    #[msg("Event has no USD price set")]
+    UsdPricingDisabled,
+    #[msg("Oracle account is not a verified SOL/USD Pyth price")]
+    InvalidOracle,
+    #[msg("Oracle price is too old")]
+    StaleOracle,
+    #[msg("Oracle confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Price exceeds the buyer's maximum")]
+    SlippageExceeded,
+    #[msg("Refund tiers must be sorted, at most 4, and at most 10000 bps")]
+    InvalidRefundPolicy,
+    #[msg("Account is not a ticket")]
+    InvalidTicketAccount,
+    #[msg("Ticket has reached its transfer limit")]
+    TransferLimitReached,
+    #[msg("Event has no lottery")]
+    NoLottery,
+    #[msg("Event already has a lottery")]
+    LotteryAlreadyOpen,
+    #[msg("This tier is allocated by lottery")]
+    LotteryTier,
+    #[msg("Lottery registration is closed")]
+    RegistrationClosed,
+    #[msg("Lottery registration is still open")]
+    RegistrationOpen,
+    #[msg("Lottery has already been drawn")]
+    LotteryAlreadyDrawn,
+    #[msg("Lottery has not been drawn yet")]
+    LotteryNotDrawn,
+    #[msg("Randomness account is invalid, stale or not yet revealed")]
+    InvalidRandomness,
+    #[msg("Entry was not drawn")]
+    NotAWinner,
+    #[msg("Winning entries claim a ticket instead of a refund")]
+    EntryWon,
+    #[msg("Voluntary refund window has closed")]
+    RefundWindowClosed,
+    #[msg("Payout splits must sum to 10000 bps")]
+    InvalidSplit,
+    #[msg("Payout split recipients don't match the event")]
+    SplitRecipientMismatch,
+    #[msg("Invalid promo code")]
+    InvalidPromoCode,
+    #[msg("Promo code has no uses left")]
+    PromoExhausted,
+    #[msg("Discount cannot exceed 100% (10000 bps)")]
+    InvalidDiscount,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Amount exceeds the withdrawable escrow balance")]
+    InsufficientWithdrawable,
+    #[msg("Token program must be the Token program or Token-2022 and own the mint")]
+    UnexpectedTokenProgram,
+    #[msg("Venue did not receive the expected post-fee amount")]
+    TransferAmountMismatch,
+    #[msg("The event's redemption window is still open")]
+    RedemptionWindowOpen,
+    #[msg("Only the ticket owner or event organizer can migrate this ticket")]
+    UnauthorizedMigration,
+    #[msg("Stats account belongs to a different event")]
+    StatsMismatch,
+    #[msg("Late entry grace cannot be negative")]
+    InvalidGracePeriod,
+    #[msg("Insurance is not offered for this event or the pool is missing")]
+    InsuranceUnavailable,
+    #[msg("Insurance pool cannot cover this payout")]
+    InsufficientInsurancePool,
+    #[msg("Event already has the maximum number of co-organizers")]
+    TooManyCoOrganizers,
+    #[msg("Co-organizer is already added")]
+    CoOrganizerAlreadyAdded,
+    #[msg("Co-organizer not found")]
+    CoOrganizerNotFound,
+    #[msg("Permissions must be a non-empty set of known flags")]
+    InvalidPermissions,
+    #[msg("Signer lacks the permission required for this action")]
+    InsufficientPermission,
+    #[msg("Only the config arbiter can resolve disputes")]
+    UnauthorizedArbiter,
+    #[msg("Dispute has already been resolved")]
+    DisputeAlreadyResolved,
+    #[msg("This event has tour stops; pass the VenueStop for the venue")]
+    VenueStopRequired,
+    #[msg("This tour stop is sold out")]
+    VenueStopSoldOut,
+    #[msg("Older waitlist entries must be promoted or gone first")]
+    WaitlistOutOfOrder,
+    #[msg("The waitlist only opens once the tier is sold out")]
+    NotSoldOut,
+    #[msg("This wallet is blacklisted from the event")]
+    AttendeeBlacklisted,
+    #[msg("This ticket is frozen")]
+    TicketFrozen,
+    #[msg("Only the organizer or arbiter can freeze tickets")]
+    UnauthorizedFreeze,
+    #[msg("A season pass needs 1 to 16 distinct events")]
+    InvalidSeasonPass,
+    #[msg("This season pass isn't valid for this event")]
+    PassNotValidForEvent,
+    #[msg("This season pass has already been used for this event")]
+    PassAlreadyUsed,
+    #[msg("The venue authority isn't a verified venue under this name")]
+    UnverifiedVenue,
+    #[msg("Only attendees with a redeemed ticket can rate")]
+    DidNotAttend,
+    #[msg("Ratings must be between 1 and 5")]
+    InvalidRating,
+    #[msg("Buyers can't refer themselves")]
+    SelfReferral,
+    #[msg("Pass the referrer's ReferralRewards account")]
+    ReferralAccountMismatch,
+    #[msg("No referral rewards to withdraw")]
+    NoReferralRewards,
+    #[msg("Not enough credits")]
+    InsufficientCredits,
+    #[msg("Credit deposits must be greater than zero")]
+    InvalidCreditAmount,
+    #[msg("The cNFT tree has no room for another mint")]
+    CnftTreeFull,
+    #[msg("Ticket belongs to a different event")]
+    TicketEventMismatch,
+    #[msg("Batch redeem must contain between 1 and 20 tickets")]
+    RedeemBatchTooLarge,
+    #[msg("The ticket expired unused when the redemption window closed")]
+    TicketExpired,
+    #[msg("A hold must end in the future")]
+    InvalidReservation,
+    #[msg("Ticket is not on hold")]
+    NotReserved,
+    #[msg("The hold on this ticket has expired")]
+    ReservationExpired,
+    #[msg("The hold on this ticket hasn't expired yet")]
+    ReservationNotExpired,
+    #[msg("Redeemed tickets can't change tier")]
+    TicketAlreadyRedeemed,
+    #[msg("Upgrades must be to a different, pricier tier and downgrades to a cheaper one")]
+    InvalidTierChange,
+    #[msg("UTC offset must be within ±840 minutes")]
+    InvalidTzOffset,
+    #[msg("The venue can't buy a ticket to its own paid event")]
+    BuyerIsVenue,
+    #[msg("Buyer doesn't have enough lamports or tokens to pay for the ticket")]
+    InsufficientFunds,
+    #[msg("Auction floor price can't be above its start price")]
+    InvalidAuction,
+    #[msg("Offered price is below the current auction price")]
+    BelowAuctionPrice,
+    #[msg("Refund exceeds what is left of the ticket's price to refund")]
+    RefundExceedsPaid,
+    #[msg("Payment method doesn't match the event's currency")]
+    CurrencyMismatch,
+    #[msg("Currency can't change once tickets have been sold")]
+    CurrencyLocked,
+    #[msg("Waitlist deposits are refunded once the event starts or is cancelled")]
+    WaitlistStillOpen,
+    #[msg("Refund sweep must contain between 1 and 20 (entry, entrant) pairs")]
+    RefundBatchTooLarge,
+    #[msg("This event requires a current attestation for the buyer")]
+    AttestationRequired,
+    #[msg("Attestations need a nonzero requirement and a future expiry")]
+    InvalidAttestation,
+    #[msg("Accepted terms don't match the event's current terms")]
+    TermsMismatch,
+    #[msg("Day index is outside the event's days")]
+    InvalidDayIndex,
+    #[msg("Ticket already checked in for this day")]
+    AlreadyCheckedInForDay,
+    #[msg("Festivals run for 1 to 8 days")]
+    InvalidFestivalDays,
+    #[msg("This sale would take the event past its revenue cap")]
+    RevenueCapReached,
+    #[msg("Group discounts must be sorted by quantity, at most 4, for 2+ tickets and at most 10000 bps")]
+    InvalidGroupDiscount,
+    #[msg("Original buyer account must be the ticket's recorded original buyer")]
+    OriginalBuyerMismatch,
+    #[msg("This feature is disabled for this deployment")]
+    FeatureDisabled,
+    #[msg("Feature flag must be a single bit")]
+    InvalidFeatureFlag,
+    #[msg("Program version cannot go backwards")]
+    InvalidProgramVersion,
+    #[msg("Account does not hold enough lamports to be rent-exempt")]
+    NotRentExempt,
+    #[msg("The ticket delegate's approval has expired")]
+    DelegateExpired,
+    #[msg("The ticket's cancel reason is already recorded")]
+    CancelReasonAlreadySet,
+    #[msg("Minimum sales must not exceed capacity and the deadline must be by the event date")]
+    InvalidMinSales,
+    #[msg("Event has reached its minimum sales and is confirmed")]
+    MinSalesMet,
+    #[msg("The minimum-sales deadline has not passed yet")]
+    ConfirmDeadlineNotPassed,
+    #[msg("Event metadata is locked once tickets are sold; force with a reason to change it")]
+    MetadataLocked,
+    #[msg("At most 5 payout split recipients are allowed")]
+    TooManySplits,
+    #[msg("Too many remaining accounts for one instruction")]
+    TooManyAccounts,
+    #[msg("Gate pass has already been settled")]
+    GatePassSettled,
+    #[msg("Gate pass does not belong to this ticket")]
+    GatePassMismatch,
+    #[msg("Offline scan is timestamped in the future")]
+    InvalidScanTime,
+    #[msg("This event only allows ownership changes through a resale")]
+    TransferRequiresSale,
+    #[msg("Resale price is below the event's minimum")]
+    ResalePriceTooLow,
+    #[msg("Transfer policy needs a minimum resale price within the maximum and at most 4 free recipients")]
+    InvalidTransferPolicy,
+    #[msg("Only matching Active general admission tickets without seats, cNFTs, insurance or token payment can be merged")]
+    TicketsNotMergeable,
+    #[msg("Group ticket must keep at least one admission after the split")]
+    InsufficientQuantity,
+    #[msg("This event has no POAP tree")]
+    PoapsNotEnabled,
+    #[msg("A POAP has already been minted for this ticket")]
+    PoapAlreadyMinted,
+    #[msg("Not the event's POAP tree or its Bubblegum tree config")]
+    InvalidTreeConfig,
+    #[msg("A presale needs an allowlist root")]
+    AllowlistRootRequired,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn sample_ticket() -> Ticket {
+        let owner = Pubkey::new_unique();
+        Ticket {
+            layout: Ticket::LAYOUT_TAG,
+            event_id: FixedStr::try_from("summer-fest").unwrap(),
+            event_name: FixedStr::try_from("Summer Fest").unwrap(),
+            event_date: 1_800_000_000,
+            venue: FixedStr::try_from("Main Stage").unwrap(),
+            attendee_name: FixedStr::try_from("Ada").unwrap(),
+            seat: Some(FixedStr::try_from("A12").unwrap()),
+            venue_authority: Pubkey::new_unique(),
+            price_paid: 5_000,
+            platform_fee: 100,
+            tier: TicketTier::Vip,
+            transferable: true,
+            metadata_uri: "ipfs://meta".to_string(),
+            redeem_nonce: 7,
+            scan_count: 0,
+            last_scanned_at: 0,
+            claim_hash: [0u8; 32],
+            claim_deadline: 0,
+            payment_mint: Pubkey::default(),
+            ticket_status: TicketStatus::Active,
+            cnft_asset_id: Pubkey::default(),
+            owner,
+            created_at: 1_700_000_000,
+            bump: 254,
+            transfer_history: Vec::new(),
+            transfer_count: 0,
+            version: Ticket::CURRENT_VERSION,
+            insured: false,
+            poap_asset_id: Pubkey::default(),
+            frozen: false,
+            venue_verified: false,
+            reserved_until: 0,
+            refunded_total: 0,
+            accepted_terms: [0u8; 32],
+            day_checked_in: 0,
+            original_buyer: owner,
+            delegate: None,
+            delegate_expiry: 0,
+            cancel_reason: None,
+            quantity: 1,
+        }
+    }
+
+    fn sample_event() -> Event {
+        let mut event =
+            Event::try_deserialize_unchecked(&mut &[0u8; Event::SIZE][..]).unwrap();
+        event.event_id = "summer-fest".to_string();
+        event.organizer = Pubkey::new_unique();
+        event.venue = "Main Stage".to_string();
+        event.venue_authority = Pubkey::new_unique();
+        event.event_date = 1_800_000_000;
+        event.capacity = 10;
+        event.tier_capacities = [8, 2, 0];
+        event.tier_prices = [1_000, 5_000, 0];
+        event.max_transfers = u8::MAX;
+        event
+    }
+
+    #[test]
+    fn unclaimed_tickets_can_be_reclaimed_after_the_deadline() {
+        let mut event = sample_event();
+        let deadline = 1_750_000_000;
+        // as issue_claimable_ticket leaves it
+        event.reserve_seat(TicketTier::GeneralAdmission).unwrap();
+        let mut ticket = sample_ticket();
+        ticket.tier = TicketTier::GeneralAdmission;
+        ticket.owner = Pubkey::default();
+        ticket.claim_hash = hash(b"code").to_bytes();
+        ticket.claim_deadline = deadline;
+        assert!(ticket.is_claimable());
+
+        ticket.check_claim(deadline).unwrap();
+        assert_eq!(
+            ticket.check_reclaim(deadline).err(),
+            Some(TicketError::ClaimNotExpired.into())
+        );
+
+        assert_eq!(
+            ticket.check_claim(deadline + 1).err(),
+            Some(TicketError::ClaimExpired.into())
+        );
+        ticket.check_reclaim(deadline + 1).unwrap();
+        event.release_seat(ticket.tier).unwrap();
+        assert_eq!(event.tickets_sold, 0);
+        assert_eq!(event.tier_sold, [0, 0, 0]);
+    }
+
+    #[test]
+    fn claimed_tickets_cannot_be_reclaimed() {
+        let deadline = 1_750_000_000;
+        let mut ticket = sample_ticket();
+        ticket.owner = Pubkey::default();
+        ticket.claim_hash = hash(b"code").to_bytes();
+        ticket.claim_deadline = deadline;
+        // as claim_ticket leaves it
+        ticket.owner = Pubkey::new_unique();
+        ticket.claim_hash = [0u8; 32];
+
+        assert!(!ticket.is_claimable());
+        assert_eq!(
+            ticket.check_claim(deadline).err(),
+            Some(TicketError::TicketNotClaimable.into())
+        );
+        assert_eq!(
+            ticket.check_reclaim(deadline + 1).err(),
+            Some(TicketError::TicketNotClaimable.into())
+        );
+    }
+
+    #[test]
+    fn only_the_venue_or_its_delegates_can_redeem() {
+        let mut event = sample_event();
+        let ticket = sample_ticket();
+        let delegate = Pubkey::new_unique();
+        event.redemption_delegates.push(delegate);
+
+        assert!(event.is_redeemer(&ticket.venue_authority, &ticket.venue_authority));
+        assert!(event.is_redeemer(&ticket.venue_authority, &delegate));
+        assert!(!event.is_redeemer(&ticket.venue_authority, &Pubkey::new_unique()));
+        assert!(!event.is_redeemer(&ticket.venue_authority, &ticket.owner));
+    }
+
+    #[test]
+    fn soulbound_comps_cannot_be_resold() {
+        let mut comp = sample_ticket();
+        comp.price_paid = 0;
+        comp.transferable = false;
+        assert_eq!(
+            comp.check_transferable().err(),
+            Some(TicketError::TicketNotTransferable.into())
+        );
+
+        let mut bought = sample_ticket();
+        bought.check_transferable().unwrap();
+        bought.ticket_status = TicketStatus::Redeemed;
+        assert_eq!(
+            bought.check_transferable().err(),
+            Some(TicketError::TicketNotTransferable.into())
+        );
+    }
+
+    #[test]
+    fn a_seat_maps_to_one_ticket_pda_per_event() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // a second purchase of A12 inits the same account, which then fails
+        // as already in use
+        assert_eq!(
+            ticket_pda("summer-fest", "Ada", Some("A12"), &first),
+            ticket_pda("summer-fest", "Bob", Some("A12"), &second)
+        );
+        assert_ne!(
+            ticket_pda("summer-fest", "Ada", Some("A12"), &first).0,
+            ticket_pda("summer-fest", "Ada", Some("A13"), &first).0
+        );
+        assert_ne!(
+            ticket_pda("summer-fest", "Ada", Some("A12"), &first).0,
+            ticket_pda("winter-fest", "Ada", Some("A12"), &first).0
+        );
+        let no_seat = Some(String::new());
+        assert_eq!(
+            Ticket::validate_fields("summer-fest", "Summer Fest", "Main Stage", "Ada", &no_seat)
+                .err(),
+            Some(TicketError::InvalidSeat.into())
+        );
+    }
+
+    #[test]
+    fn refunds_follow_the_ticket_to_its_current_owner() {
+        let mut event = sample_event();
+        let mut ticket = sample_ticket();
+        let buyer = ticket.owner;
+        let holder = Pubkey::new_unique();
+        ticket.record_transfer(holder, &event, 1_750_000_000).unwrap();
+        event.status = EventStatus::Cancelled;
+
+        // ClaimRefund pays `owner`, which must be `ticket.owner`
+        assert_eq!(ticket.owner, holder);
+        assert_eq!(ticket.original_buyer, buyer);
+        assert_eq!(ticket.transfer_history, vec![buyer]);
+        assert_eq!(
+            ticket.effective_status(&event, i64::MAX),
+            TicketStatus::Active
+        );
+        assert_eq!(ticket.refundable_amount(), 4_900);
+
+        ticket.cancel(CancelReason::EventCancelled).unwrap();
+        assert_eq!(ticket.ticket_status, TicketStatus::Cancelled);
+    }
+
+    #[test]
+    fn purchases_before_sales_start_are_rejected() {
+        let mut event = sample_event();
+        let start = 1_750_000_000;
+        event.sales_start = start;
+
+        assert_eq!(
+            event.check_sales_window(start - 1).err(),
+            Some(TicketError::SalesNotOpen.into())
+        );
+        event.check_sales_window(start).unwrap();
+        // sales_end == 0 stays open until sold out
+        event.check_sales_window(i64::MAX).unwrap();
+
+        event.sales_end = start + 60;
+        event.check_sales_window(start + 60).unwrap();
+        assert_eq!(
+            event.check_sales_window(start + 61).err(),
+            Some(TicketError::SalesClosed.into())
+        );
+    }
+
+    #[test]
+    fn max_transfers_of_one_allows_a_single_transfer() {
+        let mut event = sample_event();
+        event.max_transfers = 1;
+        let mut ticket = sample_ticket();
+        let now = 1_750_000_000;
+
+        ticket.record_transfer(Pubkey::new_unique(), &event, now).unwrap();
+        assert_eq!(ticket.transfer_count, 1);
+        let holder = ticket.owner;
+        assert_eq!(
+            ticket.record_transfer(Pubkey::new_unique(), &event, now).err(),
+            Some(TicketError::TransferLimitReached.into())
+        );
+        assert_eq!(ticket.owner, holder);
+
+        event.max_transfers = 0;
+        assert_eq!(
+            sample_ticket().record_transfer(holder, &event, now).err(),
+            Some(TicketError::TransferLimitReached.into())
+        );
+    }
+
+    #[test]
+    fn a_cancelled_ticket_frees_its_seat_for_sale() {
+        let mut event = sample_event();
+        let now = 1_750_000_000;
+        for _ in 0..8 {
+            event.record_sale(TicketTier::GeneralAdmission, 1_000, now).unwrap();
+        }
+        assert_eq!(
+            event.record_sale(TicketTier::GeneralAdmission, 1_000, now).err(),
+            Some(TicketError::SoldOut.into())
+        );
+
+        event.release_seat(TicketTier::GeneralAdmission).unwrap();
+        event.record_sale(TicketTier::GeneralAdmission, 1_000, now).unwrap();
+        assert_eq!(event.tickets_sold, 8);
+        assert_eq!(event.tier_sold, [8, 0, 0]);
+
+        assert_eq!(
+            event.release_seat(TicketTier::Vip).err(),
+            Some(TicketError::ArithmeticOverflow.into())
+        );
+    }
+
+    #[test]
+    fn early_bird_price_ends_at_the_deadline() {
+        let mut event = sample_event();
+        let deadline = 1_750_000_000;
+        event.early_bird_deadline = deadline;
+        event.early_bird_bps = 2_000;
+        let tier = TicketTier::GeneralAdmission;
+
+        assert_eq!(event.current_price(tier, deadline - 1).unwrap(), 800);
+        assert_eq!(event.current_price(tier, deadline).unwrap(), 1_000);
+        assert_eq!(
+            event.record_sale(tier, 800, deadline).err(),
+            Some(TicketError::PriceMismatch.into())
+        );
+        event.record_sale(tier, 800, deadline - 1).unwrap();
+        event.record_sale(tier, 1_000, deadline).unwrap();
+    }
+
+    #[test]
+    fn late_entry_grace_extends_redemption_past_the_start() {
+        let mut event = sample_event();
+        let start = event.event_date;
+        event.redeem_window_start = start - 2 * 60 * 60;
+        event.redeem_window_end = start;
+        event.late_entry_grace = 60 * 60;
+
+        event.check_redemption_window(start + 30 * 60).unwrap();
+        event.check_redemption_window(start + 60 * 60).unwrap();
+        assert_eq!(
+            event.check_redemption_window(start + 90 * 60).err(),
+            Some(TicketError::OutsideRedemptionWindow.into())
+        );
+
+        event.late_entry_grace = 0;
+        assert_eq!(
+            event.check_redemption_window(start + 30 * 60).err(),
+            Some(TicketError::OutsideRedemptionWindow.into())
+        );
+    }
+
+    #[test]
+    fn upgrading_into_a_sold_out_tier_fails() {
+        let mut event = sample_event();
+        let mut ticket = sample_ticket();
+        ticket.tier = TicketTier::GeneralAdmission;
+        event.reserve_seat(TicketTier::GeneralAdmission).unwrap();
+        // both VIP seats gone
+        event.reserve_seat(TicketTier::Vip).unwrap();
+        event.reserve_seat(TicketTier::Vip).unwrap();
+
+        ticket.check_tier_change(TicketTier::Vip).unwrap();
+        assert_eq!(
+            event.move_seat(ticket.tier, TicketTier::Vip).err(),
+            Some(TicketError::SoldOut.into())
+        );
+        assert_eq!(event.tier_sold, [1, 2, 0]);
+
+        ticket.ticket_status = TicketStatus::Redeemed;
+        assert_eq!(
+            ticket.check_tier_change(TicketTier::Vip).err(),
+            Some(TicketError::TicketAlreadyRedeemed.into())
+        );
+    }
+
+    #[test]
+    fn a_sale_may_reach_the_revenue_cap_but_not_pass_it() {
+        let mut event = sample_event();
+        event.revenue_cap = 3_000;
+
+        event.record_revenue(2_000).unwrap();
+        event.record_revenue(1_000).unwrap();
+        assert_eq!(event.gross_revenue, 3_000);
+        assert_eq!(
+            event.record_revenue(1).err(),
+            Some(TicketError::RevenueCapReached.into())
+        );
+        assert_eq!(event.gross_revenue, 3_000);
+
+        event.revenue_cap = 0;
+        event.record_revenue(u64::MAX - 3_000).unwrap();
+    }
+
+    #[test]
+    fn the_payer_is_left_paying_rent_exactly() {
+        let rent = Rent::default();
+        let required = rent.minimum_balance(Ticket::SIZE);
+        let (payer_key, ticket_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let start = 10 * required;
+        // init charged the payer for rent plus an over-estimate
+        let mut payer_lamports = start - required - 1_000;
+        let mut ticket_lamports = required + 1_000;
+        let (mut payer_data, mut ticket_data) = (Vec::new(), vec![0u8; Ticket::SIZE]);
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_program::ID,
+            false,
+            0,
+        );
+        let ticket = AccountInfo::new(
+            &ticket_key,
+            false,
+            true,
+            &mut ticket_lamports,
+            &mut ticket_data,
+            &crate::ID,
+            false,
+            0,
+        );
+
+        return_surplus_above(&rent, &ticket, &payer).unwrap();
+        assert_eq!(start - payer.lamports(), required);
+        assert_eq!(ticket.lamports(), required);
+
+        **ticket.try_borrow_mut_lamports().unwrap() = required - 1;
+        assert_eq!(
+            return_surplus_above(&rent, &ticket, &payer).err(),
+            Some(TicketError::NotRentExempt.into())
+        );
+    }
+
+    #[test]
+    fn migrates_a_v1_string_layout_ticket() {
+        let old = sample_ticket();
+        let mut data = Ticket::DISCRIMINATOR.to_vec();
+        LegacyTicketText {
+            event_id: old.event_id.to_string(),
+            event_name: old.event_name.to_string(),
+            event_date: old.event_date,
+            venue: old.venue.to_string(),
+            attendee_name: old.attendee_name.to_string(),
+            seat: old.seat.map(String::from),
+        }
+        .serialize(&mut data)
+        .unwrap();
+        // v1 ends at `bump`
+        (
+            old.venue_authority,
+            old.price_paid,
+            old.platform_fee,
+            old.tier,
+            old.transferable,
+            old.metadata_uri.clone(),
+            old.redeem_nonce,
+            old.scan_count,
+            old.last_scanned_at,
+            old.claim_hash,
+            old.claim_deadline,
+        )
+            .serialize(&mut data)
+            .unwrap();
+        (
+            old.payment_mint,
+            old.ticket_status.clone(),
+            old.cnft_asset_id,
+            old.owner,
+            old.created_at,
+            old.bump,
+        )
+            .serialize(&mut data)
+            .unwrap();
+
+        let ticket = Ticket::decode_any_version(&data).unwrap();
+        assert_eq!(ticket.layout, Ticket::LAYOUT_TAG);
+        assert_eq!(ticket.version, 0);
+        assert_eq!(ticket.event_id, *"summer-fest");
+        assert_eq!(ticket.event_name, *"Summer Fest");
+        assert_eq!(ticket.venue, *"Main Stage");
+        assert_eq!(ticket.attendee_name, *"Ada");
+        assert_eq!(ticket.seat.as_deref(), Some("A12"));
+        assert_eq!(ticket.venue_authority, old.venue_authority);
+        assert_eq!(ticket.price_paid, 5_000);
+        assert_eq!(ticket.tier, TicketTier::Vip);
+        assert_eq!(ticket.metadata_uri, "ipfs://meta");
+        assert_eq!(ticket.owner, old.owner);
+        assert_eq!(ticket.bump, 254);
+        assert!(ticket.transfer_history.is_empty());
+        assert_eq!(ticket.original_buyer, Pubkey::default());
+        assert_eq!(ticket.admits(), 1);
+    }
+
+    #[test]
+    fn pads_a_short_fixed_layout_ticket() {
+        let mut data = Vec::new();
+        sample_ticket().try_serialize(&mut data).unwrap();
+        // drop `quantity`, as a v13 account would
+        data.truncate(data.len() - 4);
+
+        let ticket = Ticket::decode_any_version(&data).unwrap();
+        assert_eq!(ticket.event_id, *"summer-fest");
+        assert_eq!(ticket.cancel_reason, None);
+        assert_eq!(ticket.quantity, 0);
+        assert_eq!(ticket.admits(), 1);
+    }
+
+    #[test]
+    fn rejects_accounts_that_are_not_tickets() {
+        let data = [0u8; Ticket::SIZE];
+        assert_eq!(
+            Ticket::decode_any_version(&data).err(),
+            Some(TicketError::InvalidTicketAccount.into())
+        );
+    }
+
+    #[test]
+    fn accepts_a_100_char_event_id() {
+        let event_id = "e".repeat(100);
+        Ticket::validate_fields(&event_id, "event", "venue", "attendee", &None).unwrap();
+        let buyer = Pubkey::new_unique();
+        let (ticket, _) = ticket_pda(&event_id, "attendee", None, &buyer);
+        assert_eq!(ticket, ticket_pda(&event_id, "attendee", None, &buyer).0);
+        assert_ne!(ticket, ticket_pda(&"e".repeat(99), "attendee", None, &buyer).0);
+    }
 }
@@ -0,0 +1,77 @@
+// =============================================
+// Checked money math
+// Every lamport/token split goes through here so overflow surfaces as
+// `ArithmeticOverflow` instead of a panic or a silent wrap
+// =============================================
+
+use anchor_lang::prelude::*;
+
+use crate::{TicketError, MAX_BPS};
+
+/// `bps` basis points of `amount`, rounded down.
+pub fn fee_of(amount: u64, bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(TicketError::ArithmeticOverflow)?
+        .checked_div(MAX_BPS as u128)
+        .ok_or(TicketError::ArithmeticOverflow)?;
+    Ok(u64::try_from(fee).map_err(|_| TicketError::ArithmeticOverflow)?)
+}
+
+/// Split `amount` into `(fee, remainder)` where `fee` is `bps` of it.
+pub fn split(amount: u64, bps: u16) -> Result<(u64, u64)> {
+    let fee = fee_of(amount, bps)?;
+    Ok((fee, sub(amount, fee)?))
+}
+
+/// `a + b`, failing on overflow.
+pub fn add(a: u64, b: u64) -> Result<u64> {
+    Ok(a.checked_add(b).ok_or(TicketError::ArithmeticOverflow)?)
+}
+
+/// `a - b`, failing on underflow.
+pub fn sub(a: u64, b: u64) -> Result<u64> {
+    Ok(a.checked_sub(b).ok_or(TicketError::ArithmeticOverflow)?)
+}
+
+/// `a * b`, failing on overflow.
+pub fn mul(a: u64, b: u64) -> Result<u64> {
+    Ok(a.checked_mul(b).ok_or(TicketError::ArithmeticOverflow)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflow<T: std::fmt::Debug>(result: Result<T>) -> bool {
+        result.err() == Some(TicketError::ArithmeticOverflow.into())
+    }
+
+    #[test]
+    fn fee_of_handles_extremes() {
+        assert_eq!(fee_of(u64::MAX, MAX_BPS).unwrap(), u64::MAX);
+        assert_eq!(fee_of(u64::MAX, 0).unwrap(), 0);
+        assert_eq!(fee_of(u64::MAX, 250).unwrap(), u64::MAX / 40);
+        assert_eq!(fee_of(0, MAX_BPS).unwrap(), 0);
+        assert!(overflow(fee_of(u64::MAX, u16::MAX)));
+    }
+
+    #[test]
+    fn split_handles_extremes() {
+        assert_eq!(split(u64::MAX, MAX_BPS).unwrap(), (u64::MAX, 0));
+        assert_eq!(split(u64::MAX, 0).unwrap(), (0, u64::MAX));
+        let (fee, rest) = split(u64::MAX, 5_000).unwrap();
+        assert_eq!(fee + rest, u64::MAX);
+        assert!(overflow(split(u64::MAX, u16::MAX)));
+    }
+
+    #[test]
+    fn add_sub_mul_fail_instead_of_wrapping() {
+        assert_eq!(add(u64::MAX - 1, 1).unwrap(), u64::MAX);
+        assert!(overflow(add(u64::MAX, 1)));
+        assert_eq!(sub(u64::MAX, u64::MAX).unwrap(), 0);
+        assert!(overflow(sub(0, 1)));
+        assert_eq!(mul(u64::MAX, 1).unwrap(), u64::MAX);
+        assert!(overflow(mul(u64::MAX, 2)));
+    }
+}
@@ -0,0 +1,105 @@
+// =============================================
+// Oracle guards
+// Staleness and confidence checks shared by every instruction that prices
+// in US dollars, on top of the raw Pyth reader
+// =============================================
+
+use anchor_lang::prelude::*;
+
+use crate::pyth;
+use crate::{TicketError, MAX_BPS};
+
+/// The Pyth SOL/USD price in `price_account`, for conversion with
+/// `pyth::usd_cents_to_lamports`. Fails with `StaleOracle` if the price was
+/// published more than `max_age_secs` before `now`, and with
+/// `OracleConfidenceTooWide` if its confidence interval exceeds
+/// `max_conf_bps` of the price.
+pub fn read_sol_usd(
+    price_account: &AccountInfo,
+    now: i64,
+    max_age_secs: i64,
+    max_conf_bps: u64,
+) -> Result<pyth::Price> {
+    let price = pyth::load_price(price_account, &pyth::SOL_USD_FEED_ID)?;
+    require!(
+        now.saturating_sub(price.publish_time) <= max_age_secs,
+        TicketError::StaleOracle
+    );
+    require!(
+        (price.conf as u128) * (MAX_BPS as u128)
+            <= (price.price.unsigned_abs() as u128) * (max_conf_bps as u128),
+        TicketError::OracleConfidenceTooWide
+    );
+    Ok(price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    const NOW: i64 = 1_800_000_000;
+    // $150.00 per SOL
+    const PRICE: i64 = 15_000_000_000;
+    const EXPONENT: i32 = -8;
+
+    fn price_update(feed_id: &[u8; 32], conf: u64, publish_time: i64) -> Vec<u8> {
+        let mut data = hash(b"account:PriceUpdateV2").to_bytes()[..8].to_vec();
+        data.extend_from_slice(&[0; 32]); // write_authority
+        data.push(1); // VerificationLevel::Full
+        data.extend_from_slice(feed_id);
+        data.extend_from_slice(&PRICE.to_le_bytes());
+        data.extend_from_slice(&conf.to_le_bytes());
+        data.extend_from_slice(&EXPONENT.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes()); // prev_publish_time
+        data
+    }
+
+    fn read(mut data: Vec<u8>, owner: Pubkey) -> Result<pyth::Price> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        read_sol_usd(&account, NOW, 60, 200)
+    }
+
+    fn fails_with(result: Result<pyth::Price>, error: TicketError) -> bool {
+        result.err() == Some(error.into())
+    }
+
+    #[test]
+    fn reads_a_fresh_price() {
+        let data = price_update(&pyth::SOL_USD_FEED_ID, 1_000_000, NOW - 10);
+        let price = read(data, pyth::PYTH_RECEIVER_ID).unwrap();
+        assert_eq!(price.price, PRICE);
+        assert_eq!(price.exponent, EXPONENT);
+        // $25 = 1/6 SOL, rounded up once rather than per cent
+        assert_eq!(pyth::usd_cents_to_lamports(2_500, &price).unwrap(), 166_666_667);
+    }
+
+    #[test]
+    fn rejects_a_stale_price() {
+        let data = price_update(&pyth::SOL_USD_FEED_ID, 1_000_000, NOW - 61);
+        assert!(fails_with(read(data, pyth::PYTH_RECEIVER_ID), TicketError::StaleOracle));
+    }
+
+    #[test]
+    fn rejects_a_wide_confidence_interval() {
+        // 2% of the price is allowed, 2.01% isn't
+        let data = price_update(&pyth::SOL_USD_FEED_ID, 300_000_000, NOW);
+        assert!(read(data, pyth::PYTH_RECEIVER_ID).is_ok());
+        let data = price_update(&pyth::SOL_USD_FEED_ID, 301_500_000, NOW);
+        assert!(fails_with(
+            read(data, pyth::PYTH_RECEIVER_ID),
+            TicketError::OracleConfidenceTooWide
+        ));
+    }
+
+    #[test]
+    fn rejects_the_wrong_owner_or_feed() {
+        let data = price_update(&pyth::SOL_USD_FEED_ID, 1_000_000, NOW);
+        assert!(fails_with(read(data, Pubkey::new_unique()), TicketError::InvalidOracle));
+        let data = price_update(&[7; 32], 1_000_000, NOW);
+        assert!(fails_with(read(data, pyth::PYTH_RECEIVER_ID), TicketError::InvalidOracle));
+    }
+}
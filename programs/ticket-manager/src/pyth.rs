@@ -0,0 +1,88 @@
+// =============================================
+// Pyth price feed reader
+// Parses the pull-oracle `PriceUpdateV2` account by hand, so the program
+// doesn't need to depend on the Pyth receiver SDK
+// =============================================
+
+use anchor_lang::prelude::*;
+
+use crate::TicketError;
+
+/// Pyth Solana Receiver program, which owns `PriceUpdateV2` accounts.
+pub const PYTH_RECEIVER_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Pyth feed id for SOL/USD.
+pub const SOL_USD_FEED_ID: [u8; 32] = [
+    0xef, 0x0d, 0x8b, 0x6f, 0xda, 0x2c, 0xeb, 0xa4, 0x1d, 0xa1, 0x5d, 0x40, 0x95, 0xd1, 0xda, 0x39,
+    0x2a, 0x0d, 0x2f, 0x8e, 0xd0, 0xc6, 0xc7, 0xbc, 0x0f, 0x4c, 0xfa, 0xc8, 0xc2, 0x80, 0xb5, 0x6d,
+];
+
+/// Anchor discriminator for `PriceUpdateV2` (sha256("account:PriceUpdateV2")[..8]).
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Borsh tag of `VerificationLevel::Full` (all Wormhole guardians signed).
+const VERIFICATION_FULL: u8 = 1;
+
+/// The fields of a Pyth price message this program uses.
+pub struct Price {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+/// Read a fully verified price for `feed_id` from a `PriceUpdateV2` account.
+pub fn load_price(account: &AccountInfo, feed_id: &[u8; 32]) -> Result<Price> {
+    require_keys_eq!(*account.owner, PYTH_RECEIVER_ID, TicketError::InvalidOracle);
+    let data = account.try_borrow_data()?;
+
+    // discriminator (8) + write_authority (32) + verification_level (1, Full)
+    const MESSAGE_START: usize = 8 + 32 + 1;
+    require!(
+        data.len() >= MESSAGE_START + 32 + 8 + 8 + 4 + 8
+            && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR
+            && data[40] == VERIFICATION_FULL,
+        TicketError::InvalidOracle
+    );
+
+    let message = &data[MESSAGE_START..];
+    require!(message[..32] == feed_id[..], TicketError::InvalidOracle);
+    let field = |at: usize, len: usize| &message[at..at + len];
+    Ok(Price {
+        price: i64::from_le_bytes(field(32, 8).try_into().unwrap()),
+        conf: u64::from_le_bytes(field(40, 8).try_into().unwrap()),
+        exponent: i32::from_le_bytes(field(48, 4).try_into().unwrap()),
+        publish_time: i64::from_le_bytes(field(52, 8).try_into().unwrap()),
+    })
+}
+
+/// Lamports needed to pay `usd_cents` at `price` (USD per SOL), rounded up.
+pub fn usd_cents_to_lamports(usd_cents: u64, price: &Price) -> Result<u64> {
+    require!(price.price > 0, TicketError::InvalidOracle);
+    // lamports = cents / 100 * 1e9 / (price * 10^exponent)
+    //          = cents * 1e7 * 10^-exponent / price
+    let mut numerator = (usd_cents as u128)
+        .checked_mul(10_000_000)
+        .ok_or(TicketError::ArithmeticOverflow)?;
+    let mut denominator = price.price as u128;
+    if price.exponent <= 0 {
+        let scale = 10u128
+            .checked_pow(price.exponent.unsigned_abs())
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        numerator = numerator
+            .checked_mul(scale)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+    } else {
+        let scale = 10u128
+            .checked_pow(price.exponent as u32)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+        denominator = denominator
+            .checked_mul(scale)
+            .ok_or(TicketError::ArithmeticOverflow)?;
+    }
+    let lamports = numerator
+        .checked_add(denominator - 1)
+        .ok_or(TicketError::ArithmeticOverflow)?
+        / denominator;
+    Ok(u64::try_from(lamports).map_err(|_| TicketError::ArithmeticOverflow)?)
+}
@@ -0,0 +1,52 @@
+// =============================================
+// Switchboard randomness reader
+// Parses On-Demand `RandomnessAccountData` by hand for the ticket lottery,
+// so the program doesn't need to depend on the Switchboard SDK
+// =============================================
+
+use anchor_lang::prelude::*;
+
+use crate::TicketError;
+
+/// Switchboard On-Demand program (mainnet), which owns randomness accounts.
+pub const SWITCHBOARD_ON_DEMAND_ID: Pubkey =
+    pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+/// Anchor discriminator for `RandomnessAccountData`.
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [10, 66, 229, 135, 220, 239, 217, 114];
+
+// Offsets past the discriminator: authority (32), queue (32), seed_slothash (32),
+// seed_slot (8), oracle (32), reveal_slot (8), value (32).
+const SEED_SLOT_OFFSET: usize = 8 + 32 + 32 + 32;
+const REVEAL_SLOT_OFFSET: usize = SEED_SLOT_OFFSET + 8 + 32;
+const VALUE_OFFSET: usize = REVEAL_SLOT_OFFSET + 8;
+
+/// The parts of a randomness account the lottery needs.
+pub struct Randomness {
+    /// Slot the request was committed at; must be fresh when committing.
+    pub seed_slot: u64,
+    /// Slot the oracle revealed at, 0 until revealed.
+    pub reveal_slot: u64,
+    pub value: [u8; 32],
+}
+
+/// Read a Switchboard On-Demand randomness account.
+pub fn load_randomness(account: &AccountInfo) -> Result<Randomness> {
+    require_keys_eq!(
+        *account.owner,
+        SWITCHBOARD_ON_DEMAND_ID,
+        TicketError::InvalidRandomness
+    );
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= VALUE_OFFSET + 32 && data[..8] == RANDOMNESS_DISCRIMINATOR,
+        TicketError::InvalidRandomness
+    );
+
+    let read_u64 = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+    Ok(Randomness {
+        seed_slot: read_u64(SEED_SLOT_OFFSET),
+        reveal_slot: read_u64(REVEAL_SLOT_OFFSET),
+        value: data[VALUE_OFFSET..VALUE_OFFSET + 32].try_into().unwrap(),
+    })
+}